@@ -45,5 +45,43 @@ pub fn from_pgn(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, to_pgn, from_pgn);
+/// Returns a movetext where the first move has `branching` subvariations,
+/// each of which is nested the same way until `depth` is reached.
+fn nested_movetext(depth: usize, branching: usize) -> String {
+    if depth == 0 {
+        return "1. Nf3 1... Nf6 2. Ng1 2... Ng8".to_string();
+    }
+
+    let subvariation = nested_movetext(depth - 1, branching);
+    let mut movetext = String::from("1. Nc3");
+
+    for _ in 0..branching {
+        movetext.push_str(" ( ");
+        movetext.push_str(&subvariation);
+        movetext.push_str(" )");
+    }
+
+    movetext.push_str(" 1... Nc6 2. Nb1 2... Nb8");
+    movetext
+}
+
+pub fn from_pgn_nested(c: &mut Criterion) {
+    let mut group = c.benchmark_group("from_pgn_nested");
+
+    for (depth, branching) in [(20, 1), (100, 1), (10, 2)] {
+        let pgn_string = nested_movetext(depth, branching);
+
+        assert!(Pgn::from_str(&pgn_string).first().unwrap().is_ok());
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("depth{depth}-branching{branching}")),
+            &pgn_string,
+            |b, pgn_string| b.iter(|| Pgn::from_str(pgn_string)),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, to_pgn, from_pgn, from_pgn_nested);
 criterion_main!(benches);
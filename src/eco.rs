@@ -1,12 +1,16 @@
-use std::fmt::{Display, Formatter};
+use std::fmt::{Display, Formatter, Write};
 use std::str::FromStr;
 use crate::EcoCategory;
+#[cfg(feature = "eco-infer")]
+use shakmaty::san::San;
 
 /// The ECO (Encyclopaedia of Chess Openings) code of an opening.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Eco {
     pub category: EcoCategory,
     subcategory: u8,
+    /// A lowercase letter some databases append to the code, e.g. the `a` in `C50a`.
+    subvariant: Option<char>,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -18,11 +22,18 @@ pub enum EcoParseError {
     InvalidCategory,
     InvalidSubcategory,
     SubcategoryGreaterThan99,
+    /// Only in [`Eco::from_str_extended`]. The code is followed by something other than a single lowercase letter.
+    InvalidSubvariant,
 }
 
-#[derive(Debug, Copy, Clone)]
+/// The error returned when constructing an [`Eco`] with a subcategory greater than 99.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct SubcategoryGreaterThan99;
 
+/// The error returned when setting an [`Eco`] subvariant that isn't a lowercase ASCII letter.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SubvariantNotLowercaseLetter;
+
 impl Eco {
     /// # Errors
     /// 
@@ -33,7 +44,8 @@ impl Eco {
         } else {
             Ok(Self {
                 category,
-                subcategory
+                subcategory,
+                subvariant: None,
             })
         }
     }
@@ -53,11 +65,129 @@ impl Eco {
             Ok(())
         }
     }
+
+    pub const fn get_subvariant(self) -> Option<char> {
+        self.subvariant
+    }
+
+    /// # Errors
+    ///
+    /// `new_subvariant` is some and not a lowercase ASCII letter.
+    pub fn set_subvariant(&mut self, new_subvariant: Option<char>) -> Result<(), SubvariantNotLowercaseLetter> {
+        if new_subvariant.is_some_and(|subvariant| !subvariant.is_ascii_lowercase()) {
+            Err(SubvariantNotLowercaseLetter)
+        } else {
+            self.subvariant = new_subvariant;
+            Ok(())
+        }
+    }
+
+    /// Like [`Eco::from_str`], but also accepts a single lowercase letter after the code,
+    /// which is stored as the subvariant (e.g. `C50a`).
+    ///
+    /// Unlike [`Eco::from_str`], this errors if there's anything else after the code.
+    ///
+    /// # Errors
+    ///
+    /// See [`EcoParseError`].
+    pub fn from_str_extended(s: &str) -> Result<Self, EcoParseError> {
+        if !s.is_ascii() {
+            return Err(EcoParseError::NotAscii);
+        }
+
+        if s.len() <= 3 {
+            return Self::from_str(s);
+        }
+
+        if s.len() > 4 {
+            return Err(EcoParseError::InvalidSubvariant);
+        }
+
+        let (code, subvariant) = s.split_at(3);
+        let mut eco = Self::from_str(code)?;
+
+        eco.set_subvariant(subvariant.chars().next()).map_err(|_| EcoParseError::InvalidSubvariant)?;
+
+        Ok(eco)
+    }
+}
+
+/// Common opening move sequences (space separated SANs) and their ECO codes. Used by [`Eco::infer_from_moves`].
+#[cfg(feature = "eco-infer")]
+const OPENINGS: &[(&str, EcoCategory, u8)] = &[
+    ("g4", EcoCategory::A, 0),
+    ("b3", EcoCategory::A, 1),
+    ("f4", EcoCategory::A, 2),
+    ("Nf3", EcoCategory::A, 4),
+    ("c4", EcoCategory::A, 10),
+    ("d4", EcoCategory::A, 40),
+    ("d4 Nf6", EcoCategory::A, 45),
+    ("d4 Nf6 c4", EcoCategory::A, 50),
+    ("d4 Nf6 c4 c5", EcoCategory::A, 56),
+    ("d4 f5", EcoCategory::A, 80),
+    ("e4", EcoCategory::B, 0),
+    ("e4 d5", EcoCategory::B, 1),
+    ("e4 Nf6", EcoCategory::B, 2),
+    ("e4 g6", EcoCategory::B, 6),
+    ("e4 d6 d4 Nf6", EcoCategory::B, 7),
+    ("e4 c6", EcoCategory::B, 10),
+    ("e4 c5", EcoCategory::B, 20),
+    ("e4 c5 Nf3", EcoCategory::B, 27),
+    ("e4 c5 Nf3 Nc6", EcoCategory::B, 30),
+    ("e4 c5 Nf3 e6", EcoCategory::B, 40),
+    ("e4 c5 Nf3 d6", EcoCategory::B, 50),
+    ("e4 e6", EcoCategory::C, 0),
+    ("e4 e5", EcoCategory::C, 20),
+    ("e4 e5 Nc3", EcoCategory::C, 25),
+    ("e4 e5 f4", EcoCategory::C, 30),
+    ("e4 e5 Nf3", EcoCategory::C, 40),
+    ("e4 e5 Nf3 d6", EcoCategory::C, 41),
+    ("e4 e5 Nf3 Nf6", EcoCategory::C, 42),
+    ("e4 e5 Nf3 Nc6", EcoCategory::C, 44),
+    ("e4 e5 Nf3 Nc6 d4 exd4 Nxd4", EcoCategory::C, 45),
+    ("e4 e5 Nf3 Nc6 Bc4", EcoCategory::C, 50),
+    ("e4 e5 Nf3 Nc6 Bc4 Nf6", EcoCategory::C, 55),
+    ("e4 e5 Nf3 Nc6 Bb5", EcoCategory::C, 60),
+    ("e4 e5 Nf3 Nc6 Bb5 a6", EcoCategory::C, 68),
+    ("d4 d5", EcoCategory::D, 0),
+    ("d4 d5 c4", EcoCategory::D, 6),
+    ("d4 d5 c4 c6", EcoCategory::D, 10),
+    ("d4 d5 c4 dxc4", EcoCategory::D, 20),
+    ("d4 d5 c4 e6", EcoCategory::D, 30),
+    ("d4 Nf6 c4 g6 Nc3 d5", EcoCategory::D, 80),
+    ("d4 Nf6 c4 e6", EcoCategory::E, 0),
+    ("d4 Nf6 c4 e6 Nc3 Bb4", EcoCategory::E, 20),
+    ("d4 Nf6 c4 g6", EcoCategory::E, 60),
+];
+
+#[cfg(feature = "eco-infer")]
+impl Eco {
+    /// Guesses the ECO code of a game from its first moves, using a small table of common openings.
+    /// The opening that matches the most moves wins.
+    ///
+    /// Returns [`None`] if the moves don't start with any known opening.
+    pub fn infer_from_moves(sans: &[San]) -> Option<Self> {
+        OPENINGS
+            .iter()
+            .filter(|(moves, ..)| {
+                let mut sans = sans.iter();
+
+                moves.split(' ').all(|opening_san| sans.next().is_some_and(|san| san.to_string() == opening_san))
+            })
+            .max_by_key(|(moves, ..)| moves.split(' ').count())
+            .and_then(|&(_, category, subcategory)| Self::new(category, subcategory).ok())
+    }
 }
 
 impl Display for Eco {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{:0>2}", <EcoCategory as Into<char>>::into(self.category), self.subcategory)
+        write!(f, "{}{:0>2}", <EcoCategory as Into<char>>::into(self.category), self.subcategory)?;
+
+        if let Some(subvariant) = self.subvariant {
+            f.write_char(subvariant)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -106,19 +236,64 @@ mod tests {
     use proptest::proptest;
     use test_case::test_case;
 
-    #[test_case(Eco { category: EcoCategory::A, subcategory: 9 }, "A09")]
-    #[test_case(Eco { category: EcoCategory::B, subcategory: 99 }, "B99")]
-    #[test_case(Eco { category: EcoCategory::C, subcategory: 9 }, "C09")]
-    #[test_case(Eco { category: EcoCategory::D, subcategory: 10 }, "D10")]
-    #[test_case(Eco { category: EcoCategory::E, subcategory: 99 }, "E99")]
-    #[test_case(Eco { category: EcoCategory::A, subcategory: 6 }, "A06")]
-    #[test_case(Eco { category: EcoCategory::B, subcategory: 12 }, "B12")]
-    #[test_case(Eco { category: EcoCategory::C, subcategory: 0 }, "C00")]
+    #[cfg(feature = "eco-infer")]
+    #[test_case("e4 e5 Nf3 Nc6 Bc4", Some("C50"))]
+    #[test_case("e4 e5 Nf3 Nc6 Bc4 Nf6 Nc3", Some("C55"))]
+    #[test_case("e4 c5 Nf3 d6 d4 cxd4", Some("B50"))]
+    #[test_case("d4 Nf6 c4 g6 Nc3 d5", Some("D80"))]
+    #[test_case("e4 e5 Nf3 Nc6 d4 exd4", Some("C44"); "longest prefix is shorter than a table entry")]
+    #[test_case("a4", None)]
+    #[test_case("", None)]
+    fn infer_from_moves(moves: &str, eco: Option<&str>) {
+        let sans = moves.split_whitespace().map(|san| San::from_str(san).unwrap()).collect::<Vec<_>>();
+
+        assert_eq!(Eco::infer_from_moves(&sans), eco.map(|eco| Eco::from_str(eco).unwrap()));
+    }
+
+    #[test_case(Eco { category: EcoCategory::A, subcategory: 9, subvariant: None }, "A09")]
+    #[test_case(Eco { category: EcoCategory::B, subcategory: 99, subvariant: None }, "B99")]
+    #[test_case(Eco { category: EcoCategory::C, subcategory: 9, subvariant: None }, "C09")]
+    #[test_case(Eco { category: EcoCategory::D, subcategory: 10, subvariant: None }, "D10")]
+    #[test_case(Eco { category: EcoCategory::E, subcategory: 99, subvariant: None }, "E99")]
+    #[test_case(Eco { category: EcoCategory::A, subcategory: 6, subvariant: None }, "A06")]
+    #[test_case(Eco { category: EcoCategory::B, subcategory: 12, subvariant: None }, "B12")]
+    #[test_case(Eco { category: EcoCategory::C, subcategory: 0, subvariant: None }, "C00")]
     fn to_string_from_string(eco: Eco, eco_str: &str) {
         assert_eq!(eco.to_string(), eco_str);
         assert_eq!(Eco::from_str(eco_str).unwrap(), eco);
     }
 
+    #[test_case("C50", None)]
+    #[test_case("C50a", Some('a'))]
+    #[test_case("A00z", Some('z'))]
+    fn from_str_extended(eco_str: &str, subvariant: Option<char>) {
+        let eco = Eco::from_str_extended(eco_str).unwrap();
+
+        assert_eq!(eco.get_subvariant(), subvariant);
+        assert_eq!(eco.to_string(), eco_str);
+    }
+
+    #[test_case("C50A")]
+    #[test_case("C50ab")]
+    #[test_case("C501")]
+    #[test_case("C50é")]
+    fn from_invalid_str_extended(eco_str: &str) {
+        assert!(Eco::from_str_extended(eco_str).is_err());
+    }
+
+    #[test_case(0, Ok(0))]
+    #[test_case(50, Ok(50))]
+    #[test_case(99, Ok(99))]
+    #[test_case(100, Err(SubcategoryGreaterThan99))]
+    #[test_case(255, Err(SubcategoryGreaterThan99))]
+    fn new(subcategory: u8, result: Result<u8, SubcategoryGreaterThan99>) {
+        assert_eq!(Eco::new(EcoCategory::C, subcategory).map(Eco::get_subcategory), result);
+
+        let mut eco = Eco::new(EcoCategory::C, 0).unwrap();
+
+        assert_eq!(eco.set_subcategory(subcategory).map(|()| eco.get_subcategory()), result);
+    }
+
     proptest! {
         #[test]
         fn from_valid_string(category in "[a-eA-E]", subcategory: u8) {
@@ -30,6 +30,8 @@
 )]
 
 mod concat_strings;
+#[cfg(feature = "serde")]
+mod variation_serde;
 
 pub(crate) use concat_strings::concat_strings;
 dry_mods::mods! {
@@ -37,8 +39,11 @@ dry_mods::mods! {
     variation,
     eco,
     eco_category,
-    time_control_field;
+    time_control_field,
+    pgn_write_config;
     pub mod pgn;
 }
 pub mod samples;
-pub use pgn::{Pgn, PgnParseError};
+pub use pgn::{Pgn, PgnParseError, PgnWarning};
+#[cfg(feature = "serde")]
+pub use pgn::PgnCollection;
@@ -1,3 +1,4 @@
+use std::fmt::Write;
 use std::num::NonZeroUsize;
 use shakmaty::Color;
 
@@ -38,6 +39,26 @@ impl MoveNumber {
         unsafe { NonZeroUsize::new_unchecked(1 + (self.0 / 2)) }
     }
 
+    /// Returns the [color](Self::color) and [number](Self::number) of this move number.
+    ///
+    /// This is the inverse of [`Self::from_color_and_number`].
+    pub const fn into_parts(self) -> (Color, NonZeroUsize) {
+        (self.color(), self.number())
+    }
+
+    /// Returns the move number of the previous ply, or [`None`] if this is [`Self::MIN`].
+    pub const fn checked_prev(self) -> Option<Self> {
+        match self.0.checked_sub(1) {
+            Some(index) => Some(Self(index)),
+            None => None,
+        }
+    }
+
+    /// Returns the move number of the previous ply, or [`Self::MIN`] if this is [`Self::MIN`].
+    pub const fn saturating_prev(self) -> Self {
+        Self(self.0.saturating_sub(1))
+    }
+
     /// Returns how many moves white has played before this move number was reached.
     /// E.g. for `MoveNumber { index: 0 }` this is 0, for `MoveNumber { index: 1 }` it is 1.
     pub const fn white_move_count(self) -> usize {
@@ -52,6 +73,43 @@ impl MoveNumber {
     pub const fn black_move_count(self) -> usize {
         self.0 / 2
     }
+
+    /// Returns the token that precedes this move in PGN movetext,
+    /// e.g. `1.` for white's first move and `1...` for black's first move.
+    pub fn to_pgn_token(self) -> String {
+        let mut token = String::with_capacity(4);
+
+        // CLIPPY: Writing to a `String` never fails.
+        #[allow(clippy::unwrap_used)]
+        self.write_pgn_token(&mut token).unwrap();
+
+        token
+    }
+
+    /// Writes the token returned by [`Self::to_pgn_token`].
+    ///
+    /// # Errors
+    ///
+    /// See [`std::fmt::Error`].
+    pub fn write_pgn_token(self, w: &mut impl Write) -> std::fmt::Result {
+        self.write_padded_pgn_token(w, 0)
+    }
+
+    /// Same as [`Self::write_pgn_token`], but pads the number with spaces on the left
+    /// until it's at least `number_width` characters long, e.g. ` 1.` with a width of 2.
+    ///
+    /// # Errors
+    ///
+    /// See [`std::fmt::Error`].
+    pub fn write_padded_pgn_token(self, w: &mut impl Write, number_width: usize) -> std::fmt::Result {
+        write!(w, "{:>number_width$}", self.number())?;
+
+        if self.color().is_white() {
+            w.write_char('.')
+        } else {
+            w.write_str("...")
+        }
+    }
 }
 
 #[cfg(test)]
@@ -59,6 +117,7 @@ impl MoveNumber {
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
+    use proptest::proptest;
     use test_case::test_case;
 
     #[test_case(MoveNumber::from_color_and_number(Color::White, NonZeroUsize::new(1).unwrap()), 0)]
@@ -95,6 +154,25 @@ mod tests {
         assert_eq!(move_number.number(), correct_number);
     }
 
+    proptest! {
+        #[test]
+        fn into_parts(is_white in proptest::bool::ANY, number in 1_usize..100_000) {
+            let color = if is_white { Color::White } else { Color::Black };
+            let number = NonZeroUsize::new(number).unwrap();
+
+            assert_eq!(MoveNumber::from_color_and_number(color, number).into_parts(), (color, number));
+        }
+    }
+
+    #[test_case(MoveNumber(0), None, MoveNumber(0))]
+    #[test_case(MoveNumber(1), Some(MoveNumber(0)), MoveNumber(0))]
+    #[test_case(MoveNumber(8), Some(MoveNumber(7)), MoveNumber(7))]
+    #[test_case(MoveNumber(199), Some(MoveNumber(198)), MoveNumber(198))]
+    fn prev(move_number: MoveNumber, checked_prev: Option<MoveNumber>, saturating_prev: MoveNumber) {
+        assert_eq!(move_number.checked_prev(), checked_prev);
+        assert_eq!(move_number.saturating_prev(), saturating_prev);
+    }
+
     #[test_case(MoveNumber(0), 0)]
     #[test_case(MoveNumber(1), 1)]
     #[test_case(MoveNumber(2), 1)]
@@ -114,4 +192,27 @@ mod tests {
     fn black_move_count(move_number: MoveNumber, correct_black_move_count: usize) {
         assert_eq!(move_number.black_move_count(), correct_black_move_count);
     }
+
+    #[test_case(MoveNumber(0), "1.")]
+    #[test_case(MoveNumber(1), "1...")]
+    #[test_case(MoveNumber(2), "2.")]
+    #[test_case(MoveNumber(3), "2...")]
+    #[test_case(MoveNumber(18), "10.")]
+    #[test_case(MoveNumber(199), "100...")]
+    fn to_pgn_token(move_number: MoveNumber, correct_token: &str) {
+        assert_eq!(move_number.to_pgn_token(), correct_token);
+    }
+
+    #[test_case(MoveNumber(0), 2, " 1.")]
+    #[test_case(MoveNumber(1), 2, " 1...")]
+    #[test_case(MoveNumber(18), 2, "10.")]
+    #[test_case(MoveNumber(199), 2, "100...")]
+    #[test_case(MoveNumber(0), 0, "1.")]
+    fn write_padded_pgn_token(move_number: MoveNumber, number_width: usize, correct_token: &str) {
+        let mut token = String::new();
+
+        move_number.write_padded_pgn_token(&mut token, number_width).unwrap();
+
+        assert_eq!(token, correct_token);
+    }
 }
\ No newline at end of file
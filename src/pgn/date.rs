@@ -2,7 +2,11 @@ use std::fmt::{Display, Formatter, Write};
 use std::num::NonZeroU8;
 use std::str::FromStr;
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+/// A PGN date, where any of the fields may be unknown.
+///
+/// Dates are ordered by year, then month, then day.
+/// An unknown field is ordered *before* any known value, so `2020.??.??` comes before `2020.01.01`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
 pub struct Date {
     pub year: Option<u16>,
     pub month: Option<NonZeroU8>,
@@ -14,6 +18,10 @@ pub enum DateValueError {
     YearGreaterThan9999,
     MonthGreaterThan12,
     DayGreaterThan31,
+    /// Only in [`Date::from_ymd`].
+    MonthIsZero,
+    /// Only in [`Date::from_ymd`].
+    DayIsZero,
 }
 
 impl Date {
@@ -43,34 +51,156 @@ impl Date {
 
         Ok(Self { year, month, day })
     }
+
+    /// Same as [`Date::new`], but takes plain integers for the month and day.
+    ///
+    /// # Errors
+    ///
+    /// - `month` or `day` is some and 0.
+    /// - See [`Date::new`].
+    pub fn from_ymd(year: Option<u16>, month: Option<u8>, day: Option<u8>) -> Result<Self, DateValueError> {
+        let month = month.map(|month| NonZeroU8::new(month).ok_or(DateValueError::MonthIsZero)).transpose()?;
+        let day = day.map(|day| NonZeroU8::new(day).ok_or(DateValueError::DayIsZero)).transpose()?;
+
+        Self::new(year, month, day)
+    }
+
+    pub const fn year(&self) -> Option<u16> {
+        self.year
+    }
+
+    /// Returns `true` if both dates have a known year and it's the same.
+    pub fn same_year(&self, other: &Self) -> bool {
+        self.year.is_some() && self.year == other.year
+    }
+
+    /// Returns `true` if the month and day are known, but the month doesn't have that many days.
+    ///
+    /// If the year is unknown, February is assumed to have 29 days.
+    pub(crate) fn has_impossible_day(&self) -> bool {
+        let (Some(month), Some(day)) = (self.month, self.day) else {
+            return false;
+        };
+
+        let max_day = match month.get() {
+            2 => match self.year {
+                Some(year) if !is_leap_year(year) => 28,
+                _ => 29,
+            },
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        };
+
+        day.get() > max_day
+    }
+
+    /// Returns `true` if `start <= self <= end`.
+    ///
+    /// This uses the [`Ord`] implementation, so unknown fields are treated as earlier than known ones.
+    /// For example, `2020.??.??` is *not* between `2020.01.01` and `2020.12.31`,
+    /// but it is between `2019.12.31` and `2020.12.31`.
+    pub fn is_between(&self, start: &Self, end: &Self) -> bool {
+        start <= self && self <= end
+    }
 }
 
-impl Display for Date {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+#[cfg(feature = "time")]
+impl Date {
+    /// Returns the amount of days from `self` to `other`, which is negative if `other` is earlier.
+    ///
+    /// Returns [`None`] if either date has an unknown field or doesn't exist (e.g. `2023.02.29`).
+    pub fn days_until(&self, other: &Self) -> Option<i64> {
+        let start = self.to_time_date()?.to_julian_day();
+        let end = other.to_time_date()?.to_julian_day();
+
+        i64::from(end).checked_sub(i64::from(start))
+    }
+
+    /// Returns the date `days` days after this one (or before, if `days` is negative).
+    ///
+    /// Returns [`None`] if this date has an unknown field or doesn't exist,
+    /// or if the result is outside of the years `0..=9999`.
+    pub fn add_days(&self, days: i64) -> Option<Self> {
+        let julian_day = i64::from(self.to_time_date()?.to_julian_day()).checked_add(days)?;
+        let date = time::Date::from_julian_day(i32::try_from(julian_day).ok()?).ok()?;
+
+        Self::new(
+            Some(u16::try_from(date.year()).ok()?),
+            NonZeroU8::new(u8::from(date.month())),
+            NonZeroU8::new(date.day()),
+        ).ok()
+    }
+
+    fn to_time_date(self) -> Option<time::Date> {
+        let month = time::Month::try_from(self.month?.get()).ok()?;
+
+        time::Date::from_calendar_date(i32::from(self.year?), month, self.day?.get()).ok()
+    }
+}
+
+const fn is_leap_year(year: u16) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// How to format a [`Date`]. See [`Date::format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DateStyle {
+    /// `YYYY.MM.DD`, the same as [`Display`]. This is the default.
+    #[default]
+    Pgn,
+    /// `YYYY-MM-DD`.
+    Iso,
+}
+
+impl Date {
+    /// Formats this date in the given style. Unknown fields are written as question marks in every style.
+    ///
+    /// [`Display`] always uses [`DateStyle::Pgn`].
+    pub fn format(&self, style: DateStyle) -> String {
+        let separator = match style {
+            DateStyle::Pgn => '.',
+            DateStyle::Iso => '-',
+        };
+        let mut string = String::with_capacity(10);
+
+        // CLIPPY: Writing to a `String` never fails.
+        #[allow(clippy::unwrap_used)]
+        self.write_with_separator(&mut string, separator).unwrap();
+
+        string
+    }
+
+    fn write_with_separator(&self, w: &mut impl Write, separator: char) -> std::fmt::Result {
         if let Some(year) = self.year {
-            f.write_str(&format!("{year:0>4}"))?;
+            w.write_str(&format!("{year:0>4}"))?;
         } else {
-            f.write_str("????")?;
+            w.write_str("????")?;
         }
 
-        f.write_char('.')?;
+        w.write_char(separator)?;
 
         if let Some(month) = self.month {
-            f.write_str(&format!("{month:0>2}"))?;
+            w.write_str(&format!("{month:0>2}"))?;
         } else {
-            f.write_str("??")?;
+            w.write_str("??")?;
         }
 
-        f.write_char('.')?;
+        w.write_char(separator)?;
 
         if let Some(day) = self.day {
-            f.write_str(&format!("{day:0>2}"))
+            w.write_str(&format!("{day:0>2}"))
         } else {
-            f.write_str("??")
+            w.write_str("??")
         }
     }
 }
 
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.write_with_separator(f, '.')
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DateParseError {
     MissingYear,
@@ -79,6 +209,17 @@ pub enum DateParseError {
     ValueError(DateValueError)
 }
 
+impl Date {
+    /// Like [`Date::from_str`], but also accepts `-` and `/` as separators, e.g. `2024-02-14` or `2024/02/14`.
+    ///
+    /// # Errors
+    ///
+    /// See [`DateParseError`].
+    pub fn from_str_lenient(s: &str) -> Result<Self, DateParseError> {
+        Self::from_str(&s.replace(['-', '/'], "."))
+    }
+}
+
 impl FromStr for Date {
     type Err = DateParseError;
 
@@ -128,6 +269,100 @@ mod tests {
         assert_eq!(Date::from_str(date_str).unwrap(), date);
     }
 
+    #[test_case("2024.02.14", "2024-02-14")]
+    #[test_case("0015.12.??", "0015-12-??")]
+    #[test_case("????.02.01", "????-02-01")]
+    #[test_case("????.??.??", "????-??-??")]
+    fn format(pgn_str: &str, iso_str: &str) {
+        let date = Date::from_str(pgn_str).unwrap();
+
+        assert_eq!(date.format(DateStyle::Pgn), pgn_str);
+        assert_eq!(date.format(DateStyle::Iso), iso_str);
+        assert_eq!(date.format(DateStyle::default()), date.to_string());
+        assert_eq!(Date::from_str_lenient(iso_str).unwrap(), date);
+    }
+
+    #[test_case(Some(2024), Some(2), Some(14), Ok("2024.02.14"))]
+    #[test_case(None, None, None, Ok("????.??.??"))]
+    #[test_case(Some(9999), Some(12), Some(31), Ok("9999.12.31"))]
+    #[test_case(Some(10_000), Some(1), Some(1), Err(DateValueError::YearGreaterThan9999))]
+    #[test_case(Some(2024), Some(13), None, Err(DateValueError::MonthGreaterThan12))]
+    #[test_case(Some(2024), None, Some(32), Err(DateValueError::DayGreaterThan31))]
+    #[test_case(Some(2024), Some(0), Some(1), Err(DateValueError::MonthIsZero))]
+    #[test_case(None, Some(1), Some(0), Err(DateValueError::DayIsZero))]
+    fn from_ymd(year: Option<u16>, month: Option<u8>, day: Option<u8>, expected: Result<&str, DateValueError>) {
+        assert_eq!(Date::from_ymd(year, month, day).map(|date| date.to_string()), expected.map(ToString::to_string));
+    }
+
+    #[test_case("2024.02.14")]
+    #[test_case("2024-02-14")]
+    #[test_case("2024/02/14")]
+    #[test_case("2024-02/14")]
+    fn from_str_lenient(date_str: &str) {
+        let date = Date::new(Some(2024), NonZeroU8::new(2), NonZeroU8::new(14)).unwrap();
+
+        assert_eq!(Date::from_str_lenient(date_str).unwrap(), date);
+        assert_eq!(Date::from_str_lenient(date_str).unwrap().to_string(), "2024.02.14");
+    }
+
+    #[test]
+    fn from_str_lenient_unknown() {
+        assert_eq!(Date::from_str_lenient("????-02-??").unwrap(), Date::new(None, NonZeroU8::new(2), None).unwrap());
+        assert!(Date::from_str("2024-02-14").is_err());
+        assert_eq!(Date::from_str_lenient("2024-02"), Err(DateParseError::MissingDay));
+    }
+
+    #[cfg(feature = "time")]
+    #[test_case("2024.02.14", "2024.02.14", 0)]
+    #[test_case("2024.02.14", "2024.03.01", 16)]
+    #[test_case("2023.12.31", "2024.01.01", 1)]
+    #[test_case("2024.01.01", "2023.01.01", -365)]
+    fn days_until_and_add_days(start: &str, end: &str, days: i64) {
+        let start = Date::from_str(start).unwrap();
+        let end = Date::from_str(end).unwrap();
+
+        assert_eq!(start.days_until(&end), Some(days));
+        assert_eq!(start.add_days(days), Some(end));
+    }
+
+    #[cfg(feature = "time")]
+    #[test_case("2024.??.14")]
+    #[test_case("????.02.14")]
+    #[test_case("2023.02.29")]
+    fn days_until_and_add_days_incomplete(date: &str) {
+        let date = Date::from_str(date).unwrap();
+        let complete = Date::from_str("2024.01.01").unwrap();
+
+        assert_eq!(date.days_until(&complete), None);
+        assert_eq!(complete.days_until(&date), None);
+        assert_eq!(date.add_days(1), None);
+    }
+
+    #[test_case("2020.06.15", "2020.01.01", "2020.12.31", true)]
+    #[test_case("2020.01.01", "2020.01.01", "2020.12.31", true)]
+    #[test_case("2020.12.31", "2020.01.01", "2020.12.31", true)]
+    #[test_case("2021.01.01", "2020.01.01", "2020.12.31", false)]
+    #[test_case("2020.??.??", "2020.01.01", "2020.12.31", false)]
+    #[test_case("2020.??.??", "2019.12.31", "2020.12.31", true)]
+    #[test_case("2020.12.??", "2020.01.01", "2020.12.31", true)]
+    #[test_case("????.??.??", "2020.01.01", "2020.12.31", false)]
+    #[test_case("2020.01.??", "2020.01.??", "2020.01.31", true)]
+    fn is_between(date: &str, start: &str, end: &str, between: bool) {
+        let date = Date::from_str(date).unwrap();
+        let start = Date::from_str(start).unwrap();
+        let end = Date::from_str(end).unwrap();
+
+        assert_eq!(date.is_between(&start, &end), between);
+    }
+
+    #[test_case("2020.01.01", "2020.12.31", true)]
+    #[test_case("2020.??.??", "2020.12.31", true)]
+    #[test_case("2020.01.01", "2021.01.01", false)]
+    #[test_case("????.01.01", "????.01.01", false)]
+    fn same_year(date1: &str, date2: &str, same: bool) {
+        assert_eq!(Date::from_str(date1).unwrap().same_year(&Date::from_str(date2).unwrap()), same);
+    }
+
     proptest! {
         #[test]
         fn invalid_year(year in 10000..u16::MAX) {
@@ -3,5 +3,6 @@ dry_mods::mods! {
     mod pub use pgn,
     date,
     outcome,
-    round;
+    round,
+    termination_reason;
 }
\ No newline at end of file
@@ -3,11 +3,14 @@ use std::str::FromStr;
 use shakmaty::Color;
 
 /// This is like [`shakmaty::Outcome`], but with an additional variant: [`Outcome::Other`].
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
 pub enum Outcome {
     Decisive { winner: Color },
     Draw,
     /// In progress, game abandoned, result otherwise unknown, or an invalid value.
+    ///
+    /// This is the default.
+    #[default]
     Other,
 }
 
@@ -22,6 +25,48 @@ impl Display for Outcome {
     }
 }
 
+impl From<shakmaty::Outcome> for Outcome {
+    fn from(outcome: shakmaty::Outcome) -> Self {
+        match outcome {
+            shakmaty::Outcome::Decisive { winner } => Self::Decisive { winner },
+            shakmaty::Outcome::Draw => Self::Draw,
+        }
+    }
+}
+
+impl Outcome {
+    /// Swaps the winner of [`Outcome::Decisive`]. [`Outcome::Draw`] and [`Outcome::Other`] are unchanged.
+    ///
+    /// Useful for looking at results from the other player's perspective.
+    #[must_use]
+    pub const fn flipped(self) -> Self {
+        match self {
+            Self::Decisive { winner } => Self::Decisive { winner: winner.other() },
+            Self::Draw | Self::Other => self,
+        }
+    }
+
+    /// Like [`Outcome::from_str`], but tolerates ASCII whitespace at the start and end of the string,
+    /// and around the hyphen.
+    ///
+    /// For example, `" 1 - 0"` and `"1/2 -1/2 "` are accepted, but `"1 / 2-1/2"` is not.
+    ///
+    /// # Errors
+    ///
+    /// See [`Outcome::from_str`].
+    // CLIPPY: The error is the same as in the `FromStr` implementation.
+    #[allow(clippy::result_unit_err)]
+    pub fn from_str_lenient(s: &str) -> Result<Self, ()> {
+        let s = s.trim_ascii();
+
+        let Some((left, right)) = s.split_once('-') else {
+            return Self::from_str(s);
+        };
+
+        Self::from_str(&crate::concat_strings!(left.trim_ascii_end(), "-", right.trim_ascii_start()))
+    }
+}
+
 impl FromStr for Outcome {
     type Err = ();
 
@@ -43,6 +88,15 @@ mod tests {
     use pretty_assertions::{assert_eq};
     use test_case::test_case;
     
+    #[test_case(Outcome::Decisive { winner: Color::White }, Outcome::Decisive { winner: Color::Black })]
+    #[test_case(Outcome::Decisive { winner: Color::Black }, Outcome::Decisive { winner: Color::White })]
+    #[test_case(Outcome::Draw, Outcome::Draw)]
+    #[test_case(Outcome::Other, Outcome::Other)]
+    fn flipped(outcome: Outcome, flipped: Outcome) {
+        assert_eq!(outcome.flipped(), flipped);
+        assert_eq!(outcome.flipped().flipped(), outcome);
+    }
+
     #[test_case(Outcome::Draw, "1/2-1/2")]
     #[test_case(Outcome::Other, "*")]
     #[test_case(Outcome::Decisive { winner: Color::White }, "1-0")]
@@ -50,5 +104,30 @@ mod tests {
     fn to_string_from_string(result: Outcome, result_str: &str) {
         assert_eq!(result.to_string(), result_str);
         assert_eq!(Outcome::from_str(result_str).unwrap(), result);
+        assert_eq!(Outcome::from_str_lenient(result_str).unwrap(), result);
+    }
+
+    #[test]
+    fn default() {
+        assert_eq!(Outcome::default(), Outcome::Other);
+        assert_eq!(Outcome::default().to_string(), "*");
+    }
+
+    #[test_case(Outcome::Decisive { winner: Color::White }, "1 - 0")]
+    #[test_case(Outcome::Decisive { winner: Color::Black }, "0-1 ")]
+    #[test_case(Outcome::Decisive { winner: Color::Black }, "\t0 -1")]
+    #[test_case(Outcome::Draw, " 1/2 - 1/2 ")]
+    #[test_case(Outcome::Other, " * ")]
+    fn from_str_lenient(result: Outcome, result_str: &str) {
+        assert_eq!(Outcome::from_str_lenient(result_str).unwrap(), result);
+        assert!(Outcome::from_str(result_str).is_err());
+    }
+
+    #[test_case("1 / 2-1/2")]
+    #[test_case("1-0-1")]
+    #[test_case("")]
+    #[test_case("10")]
+    fn from_str_lenient_invalid(result_str: &str) {
+        assert!(Outcome::from_str_lenient(result_str).is_err());
     }
 }
\ No newline at end of file
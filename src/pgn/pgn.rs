@@ -1,46 +1,147 @@
+use std::borrow::Cow;
 use std::fmt::{Display, Formatter, Write};
 use std::io::Read;
+use std::ops::RangeInclusive;
 use pgn_reader::BufferedReader;
+use shakmaty::{CastlingMode, Chess, Color, EnPassantMode, Position};
+use shakmaty::fen::{Epd, Fen};
+use shakmaty::san::San;
+use shakmaty::uci::Uci;
 use super::visitor::Visitor;
-use crate::{Eco, pgn::{Outcome, Date, Round}, Variation, VariationSanPlayError};
+use crate::{Eco, EcoCategory, MoveNumber, pgn::{Outcome, Date, Round, TerminationReason}, PgnWriteConfig, TurnsCapacity, Variation, VariationSanPlayError, VariationsCapacity, VariationUciPlayError};
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// The default value has no headers and no movetext.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
 pub struct Pgn {
     pub event: Option<String>,
     pub site: Option<String>,
     pub date: Option<Date>,
+    /// The `EventDate` header, i.e. when the event started. Not to be confused with [`Pgn::date`].
+    pub event_date: Option<Date>,
     pub round: Option<Round>,
     pub white: Option<String>,
     pub white_elo: Option<u16>,
     pub black: Option<String>,
     pub black_elo: Option<u16>,
+    /// The `Result` header. See also [`Pgn::movetext_outcome`].
     pub outcome: Option<Outcome>,
     pub eco: Option<Eco>,
+    /// The `Opening` header, e.g. `Italian Game`.
+    pub opening: Option<String>,
+    /// The `Variation` header, e.g. `Giuoco Piano`. Not to be confused with [`Pgn::root_variation`].
+    pub opening_variation: Option<String>,
     // TODO: Make a time control type
     pub time_control: Option<String>,
+    /// The `Termination` header, e.g. `Viih_Sou won by resignation`. See [`Pgn::result_with_reason`].
+    pub termination: Option<String>,
+    /// The `SetUp` header, `1` ([`true`]) or `0` ([`false`]). It should be `1` if there's a `FEN` header.
+    ///
+    /// If this is [`None`], `1` is written if there's a `FEN` header.
+    pub set_up: Option<bool>,
+    /// The `FEN` header, i.e. the position the game starts in.
+    pub fen: Option<String>,
+    /// Why [`Pgn::root_variation`] couldn't be constructed, if it couldn't.
+    ///
+    /// If this is [`Some`], the movetext was skipped and [`Pgn::root_variation`] is [`None`].
+    pub root_position_error: Option<RootPositionError>,
     pub root_variation: Option<Variation>,
+    /// The result token at the end of the movetext, e.g. the `1-0` in `1. e4 e5 1-0`.
+    /// It's written after the movetext if there is one. Not to be confused with [`Pgn::outcome`], the `Result` header.
+    pub movetext_outcome: Option<Outcome>,
+}
+
+/// Why the starting position of a [`Pgn`] couldn't be determined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RootPositionError {
+    /// The `FEN` header couldn't be parsed.
+    InvalidFen,
+    /// The `FEN` header is a valid setup, but not a legal position (e.g. the side not to move is in check).
+    InvalidFenPosition,
 }
 
 #[derive(Debug)]
 pub enum PgnParseError {
     Io(std::io::Error),
-    SanError(VariationSanPlayError)
+    SanError(VariationSanPlayError),
+    /// The movetext contains a null move (`--`), which a [`Variation`] can't store.
+    /// `turn_index` is the ply index of the null move, counted from the first position, like in [`VariationSanPlayError`].
+    NullMove { turn_index: usize },
+}
+
+/// A problem with a [`Pgn`] that doesn't prevent it from being parsed.
+///
+/// See [`Pgn::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnWarning {
+    /// One of the Seven Tag Roster headers is missing. Contains the header name.
+    MissingSevenTagRosterHeader(&'static str),
+    /// A string header is present, but empty. Contains the header name.
+    EmptyHeader(&'static str),
+    /// The rating is outside of [`Pgn::PLAUSIBLE_ELO`].
+    ImplausibleElo { color: Color, elo: u16 },
+    /// The month doesn't have that many days (e.g. `2023.02.29`).
+    ImpossibleDate(Date),
+    /// The `Result` header disagrees with the result token at the end of the movetext ([`Pgn::movetext_outcome`]),
+    /// or with how the movetext ended: checkmate, stalemate, insufficient material, fivefold repetition or the 75-move rule.
+    ResultDisagreesWithMovetext { header: Outcome, movetext: Outcome },
+    /// There's a `FEN` header, but the `SetUp` header isn't `1`.
+    FenWithoutSetUp,
+    /// The result is `*`, i.e. unknown or the game is still in progress.
+    ///
+    /// A `Result` header that can't be parsed at all is a [`PgnWarning::MissingSevenTagRosterHeader`] instead.
+    UnknownResult,
 }
 
 impl Pgn {
+    /// The range of ratings that [`Pgn::validate`] doesn't warn about.
+    pub const PLAUSIBLE_ELO: RangeInclusive<u16> = 100..=3500;
+
     #[allow(clippy::should_implement_trait)]
     /// Reads all games in this string.
     ///
+    /// A leading byte order mark and leading whitespace are ignored.
+    ///
     /// # Errors
     ///
     /// These are errors for every item in the `Vec`. This function does not error itself.
     /// See [`PgnParseError`].
     pub fn from_str(pgn: &str) -> Vec<Result<Self, PgnParseError>> {
+        Self::from_bytes(pgn.as_bytes())
+    }
+    
+    /// Reads all games in these bytes, which don't have to be valid UTF-8.
+    ///
+    /// Header values that aren't valid UTF-8 are decoded as Latin-1, which older PGN files often use.
+    /// A leading UTF-8 byte order mark (common in files exported on Windows) and leading whitespace are ignored.
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::from_str`].
+    pub fn from_bytes(pgn: &[u8]) -> Vec<Result<Self, PgnParseError>> {
+        let pgn = pgn.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(pgn).trim_ascii_start();
         let mut reader = pgn_reader::BufferedReader::new_cursor(pgn);
-        
+
         Self::from_reader(&mut reader)
     }
-    
+
+    /// Same as [`Self::from_str`], but parses the games in parallel.
+    ///
+    /// The string is first split into games, which is cheap compared to parsing.
+    /// The games are returned in the same order as [`Self::from_str`] would.
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::from_str`].
+    #[cfg(feature = "rayon")]
+    pub fn par_from_str(pgn: &str) -> Vec<Result<Self, PgnParseError>> {
+        use rayon::prelude::*;
+
+        split_games(pgn.strip_prefix('\u{FEFF}').unwrap_or(pgn).as_bytes())
+            .into_par_iter()
+            .flat_map_iter(Self::from_bytes)
+            .collect()
+    }
+
     /// Reads all games in this reader.
     ///
     /// It is guaranteed that the resulting `Vec` will have the same amount of games as the reader does.
@@ -61,6 +162,7 @@ impl Pgn {
             match result {
                 Ok(Some(())) => match pgn_visitor.into_pgn() {
                     Ok(pgn) => pgns.push(Ok(pgn)),
+                    Err(e) if e.san == San::Null => pgns.push(Err(PgnParseError::NullMove { turn_index: e.turn_index })),
                     Err(e) => pgns.push(Err(PgnParseError::SanError(e))),
                 },
                 Err(e) => pgns.push(Err(PgnParseError::Io(e))),
@@ -71,63 +173,616 @@ impl Pgn {
 
         pgns
     }
-}
 
-impl Display for Pgn {
-    /// Returns the string representation of this PGN.
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        macro_rules! push_pgn_header {
-            ($field_name:ident) => {
-                if let Some($field_name) = &self.$field_name {
-                    paste::paste! {
-                        f.write_str(&crate::concat_strings!("[", stringify!([<$field_name:camel>]), " \"", $field_name, "\"]\n"))?;
-                    }
-                }
-            };
+    /// Replays UCI moves (e.g. engine output) from `start`, returning a [`Pgn`] with no headers.
+    ///
+    /// If `start` isn't the standard starting position, the `FEN` header is set.
+    ///
+    /// # Errors
+    ///
+    /// See [`VariationUciPlayError`]. `turn_index` is the index of the illegal move in `uci_moves`.
+    pub fn from_uci(start: &Chess, uci_moves: &[Uci]) -> Result<Self, VariationUciPlayError> {
+        let mut root_variation = Variation::new(start.clone(), TurnsCapacity(uci_moves.len()));
 
-            ($field_name:ident, $header_title:expr) => {
-                if let Some($field_name) = &self.$field_name {
-                    paste::paste! {
-                        f.write_str(&crate::concat_strings!("[", $header_title, " \"", $field_name, "\"]\n"))?;
-                    }
-                }
-            };
+        for uci in uci_moves {
+            root_variation.play_uci(uci, VariationsCapacity(0))?;
+        }
 
-            (non_str_display: $field_name:ident) => {
-                if let Some($field_name) = &self.$field_name {
-                    paste::paste! {
-                        f.write_str(&crate::concat_strings!("[", stringify!([<$field_name:camel>]), " \"", &$field_name.to_string(), "\"]\n"))?;
-                    }
+        let fen = (*start != Chess::default()).then(|| Fen::from_position(start.clone(), EnPassantMode::Legal).to_string());
+
+        Ok(Self {
+            set_up: fen.is_some().then_some(true),
+            fen,
+            root_variation: Some(root_variation),
+            ..Self::default()
+        })
+    }
+
+    /// Returns the position the game starts in: the position of the `FEN` header,
+    /// or the standard starting position if there's no `FEN` header.
+    ///
+    /// Chess960 castling rights are recognized if the position isn't valid with standard castling.
+    ///
+    /// # Errors
+    ///
+    /// See [`RootPositionError`].
+    pub fn start_position(&self) -> Result<Chess, RootPositionError> {
+        self.fen.as_deref().map_or_else(|| Ok(Chess::default()), position_from_fen)
+    }
+
+    /// Returns `true` if there's no `FEN` header, or if it describes the standard starting position.
+    ///
+    /// The move counters of the FEN are ignored. An invalid FEN is not the standard starting position.
+    pub fn starts_from_standard_position(&self) -> bool {
+        if self.fen.is_none() {
+            return true;
+        }
+
+        self.start_position().is_ok_and(|position| {
+            Epd::from_position(position, EnPassantMode::Legal).to_string() == Epd::from_position(Chess::default(), EnPassantMode::Legal).to_string()
+        })
+    }
+
+    /// Writes `games` as a single PGN database, separating the games with an empty line.
+    /// The output ends with a single newline, unless there are no games.
+    ///
+    /// The result can be read back with [`Self::from_reader`] or [`Self::from_str`].
+    ///
+    /// # Errors
+    ///
+    /// See [`std::io::Error`].
+    pub fn write_all<'a>(games: impl IntoIterator<Item = &'a Self>, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        let mut games = games.into_iter();
+
+        let Some(first_game) = games.next() else {
+            return Ok(());
+        };
+
+        w.write_all(first_game.to_pgn().as_bytes())?;
+
+        for game in games {
+            w.write_all(b"\n\n")?;
+            w.write_all(game.to_pgn().as_bytes())?;
+        }
+
+        w.write_all(b"\n")
+    }
+
+    /// Collects the problems of this PGN that don't prevent it from being parsed.
+    ///
+    /// See [`PgnWarning`].
+    pub fn validate(&self) -> Vec<PgnWarning> {
+        let mut warnings = Vec::new();
+
+        for (header, is_missing) in [
+            ("Event", self.event.is_none()),
+            ("Site", self.site.is_none()),
+            ("Date", self.date.is_none()),
+            ("Round", self.round.is_none()),
+            ("White", self.white.is_none()),
+            ("Black", self.black.is_none()),
+            ("Result", self.outcome.is_none()),
+        ] {
+            if is_missing {
+                warnings.push(PgnWarning::MissingSevenTagRosterHeader(header));
+            }
+        }
+
+        for (header, value) in [
+            ("Event", &self.event),
+            ("Site", &self.site),
+            ("White", &self.white),
+            ("Black", &self.black),
+            ("TimeControl", &self.time_control),
+        ] {
+            if value.as_ref().is_some_and(String::is_empty) {
+                warnings.push(PgnWarning::EmptyHeader(header));
+            }
+        }
+
+        for color in [Color::White, Color::Black] {
+            if let Some(elo) = self.elo(color) {
+                if !Self::PLAUSIBLE_ELO.contains(&elo) {
+                    warnings.push(PgnWarning::ImplausibleElo { color, elo });
                 }
-            };
+            }
+        }
 
-            (non_str_display: $field_name:ident, $header_title:expr) => {
-                if let Some($field_name) = &self.$field_name {
-                    paste::paste! {
-                        f.write_str(&crate::concat_strings!("[", $header_title, " \"", &$field_name.to_string(), "\"]\n"))?;
-                    }
+        if let Some(date) = self.date {
+            if date.has_impossible_day() {
+                warnings.push(PgnWarning::ImpossibleDate(date));
+            }
+        }
+
+        if let (Some(header), Some(root_variation)) = (self.outcome, &self.root_variation) {
+            let position = root_variation.position_after_last_move();
+            // Unlike threefold repetition and the 50-move rule, these end the game without being claimed.
+            let movetext = position.outcome().map(Outcome::from).or_else(|| {
+                (root_variation.max_repetition_count() >= 5 || position.halfmoves() >= 150).then_some(Outcome::Draw)
+            });
+
+            if let Some(movetext) = movetext {
+                if header != movetext {
+                    warnings.push(PgnWarning::ResultDisagreesWithMovetext { header, movetext });
                 }
-            };
+            }
+        }
+
+        if let (Some(header), Some(movetext)) = (self.outcome, self.movetext_outcome) {
+            if header != movetext {
+                warnings.push(PgnWarning::ResultDisagreesWithMovetext { header, movetext });
+            }
+        }
+
+        if self.fen.is_some() && self.set_up != Some(true) {
+            warnings.push(PgnWarning::FenWithoutSetUp);
+        }
+
+        if self.outcome == Some(Outcome::Other) {
+            warnings.push(PgnWarning::UnknownResult);
+        }
+
+        warnings
+    }
+
+    /// Fills in the missing Seven Tag Roster headers with the placeholders the PGN standard uses for unknown values:
+    /// `?` for Event, Site, White and Black, `????.??.??` for Date, `?` for Round and `*` for Result.
+    ///
+    /// The resulting PGN never has a [`PgnWarning::MissingSevenTagRosterHeader`].
+    #[must_use]
+    pub fn with_seven_tag_roster_defaults(self) -> Self {
+        let unknown = || Some("?".to_string());
+
+        Self {
+            event: self.event.or_else(unknown),
+            site: self.site.or_else(unknown),
+            date: self.date.or(Some(Date { year: None, month: None, day: None })),
+            round: self.round.or_else(|| Some(Round::Unknown)),
+            white: self.white.or_else(unknown),
+            black: self.black.or_else(unknown),
+            outcome: self.outcome.or(Some(Outcome::Other)),
+            ..self
         }
+    }
 
-        push_pgn_header!(event);
-        push_pgn_header!(site);
-        push_pgn_header!(non_str_display: date);
-        push_pgn_header!(non_str_display: round);
-        push_pgn_header!(white);
-        push_pgn_header!(black);
-        push_pgn_header!(non_str_display: outcome, "Result");
-        push_pgn_header!(non_str_display: white_elo);
-        push_pgn_header!(non_str_display: black_elo);
-        push_pgn_header!(non_str_display: eco, "ECO");
-        push_pgn_header!(time_control);
+    /// Returns a copy of the headers (including `FEN`) without the movetext, which isn't cloned.
+    ///
+    /// Useful for deriving new games from this one, e.g. puzzles.
+    /// See also [`Self::into_headers_only`] and [`Self::with_root_variation`].
+    pub fn clone_headers(&self) -> Self {
+        Self {
+            event: self.event.clone(),
+            site: self.site.clone(),
+            date: self.date,
+            event_date: self.event_date,
+            round: self.round.clone(),
+            white: self.white.clone(),
+            white_elo: self.white_elo,
+            black: self.black.clone(),
+            black_elo: self.black_elo,
+            outcome: self.outcome,
+            eco: self.eco,
+            opening: self.opening.clone(),
+            opening_variation: self.opening_variation.clone(),
+            time_control: self.time_control.clone(),
+            termination: self.termination.clone(),
+            set_up: self.set_up,
+            fen: self.fen.clone(),
+            root_position_error: None,
+            root_variation: None,
+            movetext_outcome: None,
+        }
+    }
 
+    /// Discards the movetext, keeping only the headers.
+    ///
+    /// [`Self::root_position_error`] and [`Self::movetext_outcome`] are cleared too, because they described the discarded movetext.
+    /// Useful for storing lightweight game records after a full parse.
+    #[must_use]
+    pub fn into_headers_only(self) -> Self {
+        Self {
+            root_position_error: None,
+            root_variation: None,
+            movetext_outcome: None,
+            ..self
+        }
+    }
+
+    /// Replaces the movetext, keeping the headers.
+    ///
+    /// [`Self::root_position_error`] and [`Self::movetext_outcome`] are cleared, because they described the old movetext.
+    #[must_use]
+    pub fn with_root_variation(self, root_variation: Variation) -> Self {
+        Self {
+            root_variation: Some(root_variation),
+            root_position_error: None,
+            movetext_outcome: None,
+            ..self
+        }
+    }
+
+    /// Same as [`Self::with_root_variation`], but in place.
+    pub fn set_root_variation(&mut self, root_variation: Variation) {
+        self.root_variation = Some(root_variation);
+        self.root_position_error = None;
+        self.movetext_outcome = None;
+    }
+
+    /// Same as [`ToString::to_string`], but reserves the approximate length of the string beforehand.
+    pub fn to_pgn(&self) -> String {
+        let mut string = String::with_capacity(self.estimated_len());
+
+        // CLIPPY: Writing to a `String` never fails.
+        #[allow(clippy::unwrap_used)]
+        write!(string, "{self}").unwrap();
+
+        string
+    }
+
+    /// A rough estimate of the length of the PGN string.
+    fn estimated_len(&self) -> usize {
+        // Header name, quotes, brackets and a newline.
+        const HEADER_OVERHEAD: usize = 16;
+        // Move number, SAN and spaces.
+        const TURN_LEN: usize = 10;
+
+        let header_len = [&self.event, &self.site, &self.white, &self.black, &self.opening, &self.opening_variation, &self.time_control, &self.termination, &self.fen]
+            .into_iter()
+            .flatten()
+            .fold(0, |len: usize, value| len.saturating_add(value.len()).saturating_add(HEADER_OVERHEAD));
+        let turn_count = self.root_variation.as_ref().map_or(0, Variation::total_turn_count);
+
+        // Date, round, result, elos and ECO are short.
+        header_len
+            .saturating_add(HEADER_OVERHEAD.saturating_mul(2).saturating_mul(6))
+            .saturating_add(turn_count.saturating_mul(TURN_LEN))
+    }
+
+    /// Returns the key and value of every header that is present, in the order they are written in the PGN.
+    ///
+    /// The Seven Tag Roster comes first, in the order the PGN standard gives.
+    pub fn headers(&self) -> impl Iterator<Item = (&'static str, Cow<'_, str>)> {
+        [
+            ("Event", self.event.as_deref().map(Cow::Borrowed)),
+            ("Site", self.site.as_deref().map(Cow::Borrowed)),
+            ("Date", self.date.map(|date| Cow::Owned(date.to_string()))),
+            ("Round", self.round.as_ref().map(|round| Cow::Owned(round.to_string()))),
+            ("White", self.white.as_deref().map(Cow::Borrowed)),
+            ("Black", self.black.as_deref().map(Cow::Borrowed)),
+            ("Result", self.outcome.map(|outcome| Cow::Owned(outcome.to_string()))),
+            ("EventDate", self.event_date.map(|date| Cow::Owned(date.to_string()))),
+            ("WhiteElo", self.white_elo.map(|elo| Cow::Owned(elo.to_string()))),
+            ("BlackElo", self.black_elo.map(|elo| Cow::Owned(elo.to_string()))),
+            ("ECO", self.eco.map(|eco| Cow::Owned(eco.to_string()))),
+            ("Opening", self.opening.as_deref().map(Cow::Borrowed)),
+            ("Variation", self.opening_variation.as_deref().map(Cow::Borrowed)),
+            ("TimeControl", self.time_control.as_deref().map(Cow::Borrowed)),
+            ("Termination", self.termination.as_deref().map(Cow::Borrowed)),
+            ("SetUp", self.set_up.map(|set_up| Cow::Borrowed(if set_up { "1" } else { "0" })).or_else(|| self.fen.as_ref().map(|_| Cow::Borrowed("1")))),
+            ("FEN", self.fen.as_deref().map(Cow::Borrowed)),
+        ].into_iter().filter_map(|(key, value)| value.map(|value| (key, value)))
+    }
+
+    /// Returns the values that a game database is usually ordered by:
+    /// event, date, round, white and black.
+    ///
+    /// Missing strings are empty and missing dates and rounds are [`None`], so they all sort first.
+    /// Sort games with `games.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()))`.
+    pub fn sort_key(&self) -> (&str, Option<Date>, Option<&Round>, &str, &str) {
+        (
+            self.event.as_deref().unwrap_or_default(),
+            self.date,
+            self.round.as_ref(),
+            self.white.as_deref().unwrap_or_default(),
+            self.black.as_deref().unwrap_or_default(),
+        )
+    }
+
+    /// Returns the amount of mainline moves (plies), or [`None`] if there's no movetext.
+    ///
+    /// Subvariations aren't counted. See [`Variation::total_turn_count`] for that.
+    pub fn move_count(&self) -> Option<usize> {
+        self.root_variation.as_ref().map(|root_variation| root_variation.turns().len())
+    }
+
+    /// Returns every mainline ply as its move number and SAN, ignoring subvariations.
+    /// Numbering starts at [`Variation::first_move_number`], so it respects the `FEN` header.
+    ///
+    /// This is easier to diff than the movetext string. Empty if there's no movetext.
+    pub fn movetext_skeleton(&self) -> Vec<(MoveNumber, String)> {
         let Some(root_variation) = &self.root_variation else {
+            return Vec::new();
+        };
+
+        let first_move_number = root_variation.first_move_number();
+
+        (0..root_variation.turns().len())
+            .map_while(|turn_i| {
+                root_variation.get_san_plus(turn_i).map(|san_plus| (MoveNumber(first_move_number.0.saturating_add(turn_i)), san_plus.to_string()))
+            })
+            .collect()
+    }
+
+    /// Returns a one-line human-readable summary, e.g. `4m9n vs tigerros0, C50, 0-1, 2024.02.14 (7 moves)`.
+    ///
+    /// Missing fields are written as `?`. The move count is omitted if there's no movetext.
+    pub fn summary(&self) -> String {
+        let eco = self.eco.map_or_else(|| "?".to_string(), |eco| eco.to_string());
+        let outcome = self.outcome.map_or_else(|| "?".to_string(), |outcome| outcome.to_string());
+        let date = self.date.map_or_else(|| "?".to_string(), |date| date.to_string());
+        let mut summary = crate::concat_strings!(
+            self.white.as_deref().unwrap_or("?"),
+            " vs ",
+            self.black.as_deref().unwrap_or("?"),
+            ", ",
+            &eco,
+            ", ",
+            &outcome,
+            ", ",
+            &date
+        );
+
+        if let Some(move_count) = self.move_count() {
+            // CLIPPY: Writing to a `String` never fails.
+            #[allow(clippy::unwrap_used)]
+            write!(summary, " ({move_count} {})", if move_count == 1 { "move" } else { "moves" }).unwrap();
+        }
+
+        summary
+    }
+
+    /// Returns `true` if `name` played either side, ignoring case.
+    pub fn played_by(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+
+        [&self.white, &self.black].into_iter().flatten().any(|player| player.to_lowercase() == name)
+    }
+
+    /// Returns `true` if the ECO code is known and in `category`.
+    pub fn has_eco_category(&self, category: EcoCategory) -> bool {
+        self.eco.is_some_and(|eco| eco.category == category)
+    }
+
+    /// Returns `true` if both ratings are known and at least `min`.
+    pub fn rating_at_least(&self, min: u16) -> bool {
+        [self.white_elo, self.black_elo].into_iter().all(|elo| elo.is_some_and(|elo| elo >= min))
+    }
+
+    /// Returns the outcome together with why the game ended, e.g. "white won by resignation".
+    ///
+    /// The outcome is [`Self::outcome`], or [`Self::movetext_outcome`] if there's no `Result` header.
+    /// [`None`] if there's neither. The reason is [`None`] if there's no `Termination` header
+    /// or it doesn't say why the game ended (see [`TerminationReason`]).
+    pub fn result_with_reason(&self) -> Option<(Outcome, Option<TerminationReason>)> {
+        self.outcome.or(self.movetext_outcome).map(|outcome| (outcome, self.termination.as_deref().and_then(|termination| termination.parse().ok())))
+    }
+
+    /// Returns [`Self::white`] or [`Self::black`], depending on `color`.
+    pub fn player(&self, color: Color) -> Option<&str> {
+        match color {
+            Color::White => self.white.as_deref(),
+            Color::Black => self.black.as_deref(),
+        }
+    }
+
+    /// Returns [`Self::white_elo`] or [`Self::black_elo`], depending on `color`.
+    pub const fn elo(&self, color: Color) -> Option<u16> {
+        match color {
+            Color::White => self.white_elo,
+            Color::Black => self.black_elo,
+        }
+    }
+}
+
+/// Splits a PGN database into the bytes of each game.
+///
+/// A game ends when a header line (a line starting with `[`) follows movetext.
+/// Brackets inside `{}` comments, `;` comments and `%` escaped lines are ignored.
+#[cfg(feature = "rayon")]
+fn split_games(pgn: &[u8]) -> Vec<&[u8]> {
+    let mut games = Vec::new();
+    let mut game_start = 0_usize;
+    let mut line_start = 0_usize;
+    let mut in_movetext = false;
+    let mut in_comment = false;
+
+    for line in pgn.split_inclusive(|&byte| byte == b'\n') {
+        let trimmed = line.trim_ascii_start();
+
+        if !in_comment {
+            match trimmed.first() {
+                Some(b'[') => {
+                    if in_movetext {
+                        games.extend(pgn.get(game_start..line_start));
+                        game_start = line_start;
+                        in_movetext = false;
+                    }
+
+                    line_start = line_start.saturating_add(line.len());
+                    continue;
+                },
+                Some(b'%') | None => {
+                    line_start = line_start.saturating_add(line.len());
+                    continue;
+                },
+                Some(_) => in_movetext = true,
+            }
+        }
+
+        for &byte in line {
+            match (in_comment, byte) {
+                (true, b'}') => in_comment = false,
+                (false, b'{') => in_comment = true,
+                (false, b';') => break,
+                _ => {},
+            }
+        }
+
+        line_start = line_start.saturating_add(line.len());
+    }
+
+    if let Some(last_game) = pgn.get(game_start..) {
+        if !last_game.trim_ascii().is_empty() {
+            games.push(last_game);
+        }
+    }
+
+    games
+}
+
+/// Parses a FEN and sets up its position, falling back to Chess960 castling if standard castling is invalid.
+pub(crate) fn position_from_fen(fen: &str) -> Result<Chess, RootPositionError> {
+    let fen = Fen::from_ascii(fen.as_bytes()).map_err(|_| RootPositionError::InvalidFen)?;
+
+    fen.clone()
+        .into_position(CastlingMode::Standard)
+        .or_else(|_| fen.into_position(CastlingMode::Chess960))
+        .map_err(|_| RootPositionError::InvalidFenPosition)
+}
+
+/// Escapes `"` as `\"` and `\` as `\\`, as required in header values.
+fn escape_header_value(value: &str) -> Cow<'_, str> {
+    if !value.contains(['"', '\\']) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut escaped = String::with_capacity(value.len().saturating_add(2));
+
+    for character in value.chars() {
+        if matches!(character, '"' | '\\') {
+            escaped.push('\\');
+        }
+
+        escaped.push(character);
+    }
+
+    Cow::Owned(escaped)
+}
+
+/// Displays a [`Pgn`] with a [`PgnWriteConfig`].
+///
+/// See [`Pgn::display_with`].
+#[derive(Debug, Clone, Copy)]
+pub struct PgnDisplay<'a> {
+    pgn: &'a Pgn,
+    config: PgnWriteConfig,
+}
+
+impl Display for PgnDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (key, value) in self.pgn.headers() {
+            let value = escape_header_value(&value);
+
+            f.write_str(&crate::concat_strings!("[", key, " \"", &value, "\"]\n"))?;
+        }
+
+        let Some(root_variation) = &self.pgn.root_variation else {
             return Ok(());
         };
 
         f.write_char('\n')?;
-        f.write_str(&root_variation.to_string())
+        Display::fmt(&root_variation.display_with(self.config), f)?;
+
+        let Some(movetext_outcome) = self.pgn.movetext_outcome else {
+            return Ok(());
+        };
+
+        if !root_variation.turns().is_empty() {
+            f.write_char(' ')?;
+        }
+
+        Display::fmt(&movetext_outcome, f)
+    }
+}
+
+impl Pgn {
+    /// Returns a value that displays the string representation of this PGN according to `config`.
+    pub const fn display_with(&self, config: PgnWriteConfig) -> PgnDisplay<'_> {
+        PgnDisplay {
+            pgn: self,
+            config,
+        }
+    }
+}
+
+impl Display for Pgn {
+    /// Returns the string representation of this PGN.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.display_with(PgnWriteConfig::default()), f)
+    }
+}
+
+/// Serializes into the PGN string.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Pgn {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes from a PGN string containing exactly one game.
+///
+/// Errors if there are no games or more than one game.
+/// Use [`PgnCollection`] to deserialize multiple games.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Pgn {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+        use serde::de::Error;
+
+        let string = String::deserialize(deserializer)?;
+        let mut pgns = Self::from_str(&string).into_iter();
+
+        let Some(pgn) = pgns.next() else {
+            return Err(D::Error::custom("no PGN found"));
+        };
+
+        if pgns.next().is_some() {
+            return Err(D::Error::custom("found more than one PGN, use `PgnCollection` to deserialize multiple games"));
+        }
+
+        pgn.map_err(|error| D::Error::custom(format!("{error:?}")))
+    }
+}
+
+/// A list of games that (de)serializes from/to a single PGN string,
+/// like the contents of a PGN database file.
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct PgnCollection(pub Vec<Pgn>);
+
+/// Serializes into the PGN strings of each game, separated by an empty line.
+#[cfg(feature = "serde")]
+impl serde::Serialize for PgnCollection {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+        let mut string = String::new();
+
+        for (i, pgn) in self.0.iter().enumerate() {
+            if i != 0 {
+                string.push_str("\n\n");
+            }
+
+            string.push_str(&pgn.to_string());
+        }
+
+        serializer.serialize_str(&string)
+    }
+}
+
+/// Deserializes from a PGN string containing any amount of games.
+///
+/// Errors if any of the games fails to parse.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PgnCollection {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+        use serde::de::Error;
+
+        let string = String::deserialize(deserializer)?;
+
+        Pgn::from_str(&string)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self)
+            .map_err(|error| D::Error::custom(format!("{error:?}")))
     }
 }
 
@@ -141,6 +796,7 @@ mod tests {
     use test_case::test_case;
     use pretty_assertions::assert_eq;
     use crate::samples::*;
+    use std::num::NonZeroU8;
 
     #[test_case(pgn_sample0())]
     #[test_case(pgn_sample1())]
@@ -173,4 +829,604 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn unknown_year_round_trip() {
+        const PGN: &str = "[Date \"????.02.14\"]\n\n1. e4";
+
+        let pgn = Pgn::from_str(PGN).pop().unwrap().unwrap();
+
+        assert_eq!(pgn.date, Some(Date::new(None, NonZeroU8::new(2), NonZeroU8::new(14)).unwrap()));
+        assert_eq!(pgn.to_string(), PGN);
+    }
+
+    #[test_case(pgn_sample0())]
+    #[test_case(pgn_sample1())]
+    #[test_case(pgn_sample2())]
+    #[test_case(pgn_sample6())]
+    fn to_pgn(sample: PgnSample) {
+        let pgn = sample.parsed.unwrap();
+
+        assert_eq!(pgn.to_pgn(), pgn.to_string());
+    }
+
+    #[test_case(pgn_sample0())]
+    #[test_case(pgn_sample1())]
+    fn validate_valid(sample: PgnSample) {
+        assert_eq!(sample.parsed.unwrap().validate(), vec![]);
+    }
+
+    #[test]
+    fn validate_invalid() {
+        const PGN: &str = r#"[Event ""]
+[Date "2023.02.29"]
+[White "Anonymous"]
+[Black ""]
+[Result "1-0"]
+[WhiteElo "5000"]
+[BlackElo "1500"]
+
+1. g4 1... e5 2. f3 2... Qh4#"#;
+
+        let pgn = Pgn::from_str(PGN).pop().unwrap().unwrap();
+
+        assert_eq!(pgn.validate(), vec![
+            PgnWarning::MissingSevenTagRosterHeader("Site"),
+            PgnWarning::MissingSevenTagRosterHeader("Round"),
+            PgnWarning::EmptyHeader("Event"),
+            PgnWarning::EmptyHeader("Black"),
+            PgnWarning::ImplausibleElo { color: Color::White, elo: 5000 },
+            PgnWarning::ImpossibleDate(Date::new(Some(2023), NonZeroU8::new(2), NonZeroU8::new(29)).unwrap()),
+            PgnWarning::ResultDisagreesWithMovetext {
+                header: Outcome::Decisive { winner: Color::White },
+                movetext: Outcome::Decisive { winner: Color::Black },
+            },
+        ]);
+    }
+
+    #[test]
+    fn validate_setup_and_result() {
+        const PGN: &str = "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n[White \"?\"]\n[Black \"?\"]\n[Result \"*\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 20\"]\n\n20... Kd7";
+
+        let pgn = Pgn::from_str(PGN).pop().unwrap().unwrap();
+
+        assert_eq!(pgn.set_up, None);
+        assert_eq!(pgn.validate(), vec![PgnWarning::FenWithoutSetUp, PgnWarning::UnknownResult]);
+        assert_eq!(pgn.to_string().replace("[SetUp \"1\"]\n", ""), PGN);
+
+        let set_up_pgn = Pgn::from_str(&PGN.replace("[FEN", "[SetUp \"1\"]\n[FEN")).pop().unwrap().unwrap();
+
+        assert_eq!(set_up_pgn.set_up, Some(true));
+        assert_eq!(set_up_pgn.validate(), vec![PgnWarning::UnknownResult]);
+    }
+
+    #[test]
+    fn validate_fivefold_repetition() {
+        let mut pgn = pgn_sample0().parsed.unwrap();
+        let mut root_variation = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        for _ in 0..4 {
+            crate::variation::play_san_strings!(root_variation, "Nf3", "Nf6", "Ng1", "Ng8").unwrap();
+        }
+
+        pgn.root_variation = Some(root_variation);
+
+        assert_eq!(pgn.validate(), vec![PgnWarning::ResultDisagreesWithMovetext {
+            header: Outcome::Decisive { winner: Color::Black },
+            movetext: Outcome::Draw,
+        }]);
+    }
+
+    #[test_case("C50", Some("C50"))]
+    #[test_case("C50a", Some("C50a"))]
+    #[test_case("C50A", Some("C50"))]
+    #[test_case("C50 ", Some("C50"))]
+    #[test_case("C50 Italian", Some("C50"))]
+    #[test_case("?", None)]
+    fn eco_header(eco: &str, parsed: Option<&str>) {
+        let pgn = Pgn::from_str(&crate::concat_strings!("[ECO \"", eco, "\"]\n\n1. e4")).pop().unwrap().unwrap();
+
+        assert_eq!(pgn.eco.map(|eco| eco.to_string()).as_deref(), parsed);
+    }
+
+    #[test]
+    fn opening_headers() {
+        const PGN: &str = "[ECO \"C50\"]\n[Opening \"Italian Game\"]\n[Variation \"Giuoco Piano\"]\n\n1. e4";
+
+        let pgn = Pgn::from_str(PGN).pop().unwrap().unwrap();
+
+        assert_eq!(pgn.opening.as_deref(), Some("Italian Game"));
+        assert_eq!(pgn.opening_variation.as_deref(), Some("Giuoco Piano"));
+        assert_eq!(pgn.to_string(), PGN);
+    }
+
+    #[test]
+    fn headers() {
+        let pgn = pgn_sample0().parsed.unwrap();
+        let headers = pgn.headers().collect::<Vec<_>>();
+
+        assert_eq!(headers, vec![
+            ("Event", Cow::Borrowed("Let's Play!")),
+            ("Site", Cow::Borrowed("Chess.com")),
+            ("Date", Cow::Borrowed("2024.02.14")),
+            ("Round", Cow::Borrowed("?")),
+            ("White", Cow::Borrowed("4m9n")),
+            ("Black", Cow::Borrowed("tigerros0")),
+            ("Result", Cow::Borrowed("0-1")),
+            ("WhiteElo", Cow::Borrowed("1490")),
+            ("BlackElo", Cow::Borrowed("1565")),
+            ("ECO", Cow::Borrowed("C50")),
+            ("TimeControl", Cow::Borrowed("600+0")),
+        ]);
+
+        let pgn = Pgn {
+            opening: Some("Italian Game".to_string()),
+            ..pgn
+        };
+
+        assert!(pgn.headers().any(|(key, value)| key == "Opening" && value == "Italian Game"));
+        assert_eq!(pgn.headers().count(), 12);
+    }
+
+    #[test]
+    fn display_with() {
+        let pgn = pgn_sample1().parsed.unwrap();
+        let config = PgnWriteConfig {
+            explicit_black_numbers: false,
+            ..PgnWriteConfig::default()
+        };
+
+        assert!(pgn.display_with(config).to_string().ends_with("\n\n1. g4 e5 2. f3 Qh4#"));
+        assert_eq!(pgn.display_with(PgnWriteConfig::default()).to_string(), pgn.to_string());
+    }
+
+    #[test_case(pgn_sample0())]
+    #[test_case(pgn_sample1())]
+    #[test_case(pgn_sample2())]
+    fn into_headers_only(sample: PgnSample) {
+        let pgn = sample.parsed.unwrap();
+        let root_variation = pgn.root_variation.clone().unwrap();
+        let headers_only = pgn.clone().into_headers_only();
+
+        assert_eq!(headers_only.root_variation, None);
+        assert_eq!(crate::concat_strings!(&headers_only.to_string(), "\n", &root_variation.to_string()), pgn.to_string());
+    }
+
+    #[test]
+    fn into_headers_only_clears_root_position_error() {
+        let pgn = Pgn::from_str("[FEN \"not a fen\"]\n\n1. e4").remove(0).unwrap();
+
+        assert_eq!(pgn.root_position_error, Some(RootPositionError::InvalidFen));
+
+        let headers_only = pgn.into_headers_only();
+
+        assert_eq!(headers_only.root_position_error, None);
+        assert_eq!(headers_only.fen.as_deref(), Some("not a fen"));
+    }
+
+    #[test_case("1. e4 -- 2. d4", 1)]
+    #[test_case("1. e4 ( 1. d4 d5 2. -- ) 1... e5", 2)]
+    fn null_move(pgn: &str, null_move_index: usize) {
+        assert!(matches!(
+            Pgn::from_str(pgn).remove(0),
+            Err(PgnParseError::NullMove { turn_index }) if turn_index == null_move_index
+        ));
+    }
+
+    #[test]
+    fn fen() {
+        let pgn = Pgn::from_str("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 20\"]\n\n20... Kd7 21. e4").remove(0).unwrap();
+        let root_variation = pgn.root_variation.as_ref().unwrap();
+
+        assert_eq!(pgn.fen.as_deref(), Some("4k3/8/8/8/8/8/4P3/4K3 b - - 0 20"));
+        assert_eq!(pgn.root_position_error, None);
+        assert_eq!(root_variation.first_position().turn(), Color::Black);
+        assert_eq!(root_variation.turns().len(), 2);
+        assert_eq!(pgn.to_string(), "[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 20\"]\n\n20... Kd7 21. e4");
+        assert_eq!(Pgn::from_str(&pgn.to_string()).remove(0).unwrap(), pgn);
+    }
+
+    #[test_case("[FEN \"not a fen\"]\n\n1. e4", RootPositionError::InvalidFen)]
+    // The side not to move is in check.
+    #[test_case("[FEN \"4k3/8/8/8/8/8/4R3/4K3 w - - 0 1\"]\n\n1. Kd1", RootPositionError::InvalidFenPosition)]
+    fn fen_error(pgn: &str, error: RootPositionError) {
+        let pgn = Pgn::from_str(pgn).remove(0).unwrap();
+
+        assert_eq!(pgn.root_position_error, Some(error));
+        assert_eq!(pgn.root_variation, None);
+        assert!(pgn.fen.is_some());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_from_str() {
+        let pgn = crate::concat_strings!(
+            pgn_sample0().string,
+            "\n\n",
+            // Comments that look like headers, but mustn't split the game.
+            "[Event \"Tricky\"]\n\n1. e4 { see\n[Event \"not a game\"] } 1... e5 ; [Event\n2. Nf3\n\n",
+            pgn_sample1().string,
+            "\n\n",
+            pgn_sample2().string
+        );
+
+        let sequential = Pgn::from_str(&pgn);
+
+        assert_eq!(super::split_games(pgn.as_bytes()).len(), 4);
+        assert_eq!(sequential.len(), 4);
+        assert_eq!(format!("{:?}", Pgn::par_from_str(&pgn)), format!("{sequential:?}"));
+    }
+
+    #[test]
+    fn event_date() {
+        let pgn = Pgn::from_str("[EventDate \"2024.02.10\"]\n[Date \"2024.02.14\"]\n[Round \"3\"]\n[Result \"1-0\"]\n\n1. e4").remove(0).unwrap();
+
+        assert_eq!(pgn.event_date, Some(Date::new(Some(2024), NonZeroU8::new(2), NonZeroU8::new(10)).unwrap()));
+        // The Seven Tag Roster comes first.
+        assert!(pgn.to_string().starts_with("[Date \"2024.02.14\"]\n[Round \"3\"]\n[Result \"1-0\"]\n[EventDate \"2024.02.10\"]\n"));
+        assert_eq!(Pgn::from_str(&pgn.to_string()).remove(0).unwrap(), pgn);
+    }
+
+    #[test]
+    fn write_all() {
+        let games = [pgn_sample0().parsed.unwrap(), pgn_sample1().parsed.unwrap()];
+        let mut bytes = Vec::new();
+
+        Pgn::write_all(&games, &mut bytes).unwrap();
+
+        let string = String::from_utf8(bytes).unwrap();
+
+        assert_eq!(string, crate::concat_strings!(pgn_sample0().string, "\n\n", pgn_sample1().string, "\n"));
+        assert_eq!(Pgn::from_str(&string).into_iter().map(Result::unwrap).collect::<Vec<_>>(), games);
+
+        let mut bytes = Vec::new();
+
+        Pgn::write_all(&[], &mut bytes).unwrap();
+
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn from_bytes() {
+        // "Müller" in Latin-1.
+        let pgn = Pgn::from_bytes(b"[White \"M\xFCller\"]\n[Event \"M\xC3\xBCnchen\"]\n\n1. e4").remove(0).unwrap();
+
+        assert_eq!(pgn.white.as_deref(), Some("Müller"));
+        // Valid UTF-8 isn't decoded as Latin-1.
+        assert_eq!(pgn.event.as_deref(), Some("München"));
+        assert_eq!(pgn.root_variation, Some(variation_sample6()));
+
+        let sample = pgn_sample0();
+
+        assert_eq!(Pgn::from_bytes(sample.string.as_bytes()).remove(0).unwrap(), sample.parsed.unwrap());
+    }
+
+    #[test_case("\u{FEFF}")]
+    #[test_case("\u{FEFF}\n\n")]
+    #[test_case("\r\n \n")]
+    fn byte_order_mark_and_leading_whitespace(prefix: &str) {
+        let sample = pgn_sample0();
+        let pgns = Pgn::from_str(&crate::concat_strings!(prefix, sample.string));
+
+        assert_eq!(pgns.len(), 1);
+        assert_eq!(pgns.into_iter().next().unwrap().unwrap(), sample.parsed.unwrap());
+    }
+
+    #[test]
+    fn duplicate_headers_last_wins() {
+        let pgn = Pgn::from_str(r#"[Event "First"]
+[White "Alice"]
+[Result "1-0"]
+[Event "Second"]
+[White "Bob"]
+[Result "0-1"]
+
+1. e4"#).remove(0).unwrap();
+
+        assert_eq!(pgn.event.as_deref(), Some("Second"));
+        assert_eq!(pgn.white.as_deref(), Some("Bob"));
+        assert_eq!(pgn.outcome, Some(Outcome::Decisive { winner: Color::Black }));
+    }
+
+    #[test]
+    fn from_uci() {
+        let ucis = ["g2g4", "e7e5", "f2f3", "d8h4"].map(|uci| Uci::from_ascii(uci.as_bytes()).unwrap());
+        let pgn = Pgn::from_uci(&Chess::new(), &ucis).unwrap();
+
+        assert_eq!(pgn.root_variation, Some(variation_sample1()));
+        assert_eq!(pgn.fen, None);
+        assert_eq!(pgn.event, None);
+
+        let g4 = Uci::from_ascii(b"g2g4").unwrap();
+        let error = Pgn::from_uci(&Chess::new(), &[g4.clone(), g4.clone()]).unwrap_err();
+
+        assert_eq!(error.turn_index, 1);
+        assert_eq!(error.uci, g4);
+    }
+
+    #[test]
+    fn from_uci_black_to_move() {
+        let start = position_from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 20").unwrap();
+        let ucis = ["e8d7", "e2e4", "d7e6"].map(|uci| Uci::from_ascii(uci.as_bytes()).unwrap());
+        let pgn = Pgn::from_uci(&start, &ucis).unwrap();
+
+        assert_eq!(pgn.to_pgn(), "[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 20\"]\n\n20... Kd7 21. e4 21... Ke6");
+        assert_eq!(Pgn::from_str(&pgn.to_pgn()).remove(0).unwrap(), pgn);
+    }
+
+    #[test]
+    fn sort_key() {
+        let sample0 = pgn_sample0().parsed.unwrap();
+        let sample1 = pgn_sample1().parsed.unwrap();
+        let sample2 = pgn_sample2().parsed.unwrap();
+
+        assert_eq!(sample2.sort_key(), ("", sample2.date, Some(&Round::Normal(1)), "", ""));
+
+        let mut games = vec![sample0.clone(), sample1.clone(), sample2.clone()];
+
+        games.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+
+        // The empty event of sample 2 sorts first, then "Let's Play!" < "Live Chess".
+        assert_eq!(games, vec![sample2, sample0, sample1]);
+    }
+
+    #[test_case(None, Ok(Chess::default()))]
+    #[test_case(Some("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"), Ok(Chess::default()))]
+    #[test_case(Some("4k3/8/8/8/8/8/4R3/4K3 w - - 0 1"), Err(RootPositionError::InvalidFenPosition))]
+    #[test_case(Some("not a fen"), Err(RootPositionError::InvalidFen))]
+    fn start_position(fen: Option<&str>, expected: Result<Chess, RootPositionError>) {
+        let pgn = Pgn {
+            fen: fen.map(ToString::to_string),
+            ..Pgn::default()
+        };
+
+        assert_eq!(pgn.start_position(), expected);
+    }
+
+    #[test_case(None, true)]
+    #[test_case(Some("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"), true)]
+    #[test_case(Some("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3 7"), true; "different move counters")]
+    #[test_case(Some("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"), false)]
+    #[test_case(Some("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w Qkq - 0 1"), false; "different castling rights")]
+    #[test_case(Some("not a fen"), false)]
+    fn starts_from_standard_position(fen: Option<&str>, expected: bool) {
+        let pgn = Pgn {
+            fen: fen.map(ToString::to_string),
+            ..Pgn::default()
+        };
+
+        assert_eq!(pgn.starts_from_standard_position(), expected);
+    }
+
+    #[test]
+    fn start_position_chess960() {
+        // The rooks are on b1 and g1, which is only valid with Chess960 castling rights.
+        let pgn = Pgn {
+            fen: Some("qrbnkbrn/pppppppp/8/8/8/8/PPPPPPPP/QRBNKBRN w GBgb - 0 1".to_string()),
+            ..Pgn::default()
+        };
+        let position = pgn.start_position().unwrap();
+
+        assert_eq!(position.castles().castling_rights().count(), 4);
+    }
+
+    #[test]
+    fn with_seven_tag_roster_defaults() {
+        let pgn = pgn_sample6().parsed.unwrap().with_seven_tag_roster_defaults();
+
+        assert!(pgn.validate().is_empty());
+        assert!(pgn.to_string().starts_with(r#"[Event "?"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "?"]
+[Black "?"]
+[Result "*"]
+
+1. e4"#));
+
+        let sample = pgn_sample0().parsed.unwrap();
+
+        assert_eq!(sample.clone().with_seven_tag_roster_defaults(), sample);
+    }
+
+    #[test_case(pgn_sample0())]
+    #[test_case(pgn_sample1())]
+    #[test_case(pgn_sample6())]
+    fn clone_headers(sample: PgnSample) {
+        let pgn = sample.parsed.unwrap();
+        let headers = pgn.clone_headers();
+
+        assert_eq!(headers.headers().collect::<Vec<_>>(), pgn.headers().collect::<Vec<_>>());
+        assert_eq!(headers.root_variation, None);
+        assert_eq!(headers, pgn.into_headers_only());
+    }
+
+    #[test]
+    fn with_root_variation() {
+        let pgn = pgn_sample0().parsed.unwrap().with_root_variation(variation_sample1());
+
+        assert_eq!(pgn.root_variation, Some(variation_sample1()));
+        assert_eq!(pgn.event.as_deref(), Some("Let's Play!"));
+
+        let pgn = Pgn::from_str("[FEN \"not a fen\"]\n\n1. e4").remove(0).unwrap().with_root_variation(variation_sample6());
+
+        assert_eq!(pgn.root_position_error, None);
+        assert_eq!(pgn.root_variation, Some(variation_sample6()));
+    }
+
+    #[test]
+    fn set_root_variation() {
+        let mut pgn = Pgn::from_str("[Event \"Casual\"]\n[FEN \"not a fen\"]\n\n1. e4 1-0").remove(0).unwrap();
+
+        pgn.set_root_variation(variation_sample6());
+
+        assert_eq!(pgn.root_position_error, None);
+        assert_eq!(pgn.movetext_outcome, None);
+        assert_eq!(pgn.root_variation, Some(variation_sample6()));
+        assert_eq!(pgn.event.as_deref(), Some("Casual"));
+    }
+
+    #[test_case(pgn_sample0(), Some(7))]
+    #[test_case(pgn_sample1(), Some(4))]
+    #[test_case(pgn_sample6(), Some(1))]
+    fn move_count(sample: PgnSample, count: Option<usize>) {
+        let pgn = sample.parsed.unwrap();
+
+        assert_eq!(pgn.move_count(), count);
+        assert_eq!(pgn.into_headers_only().move_count(), None);
+    }
+
+    #[test_case(pgn_sample0(), "4m9n vs tigerros0, C50, 0-1, 2024.02.14 (7 moves)")]
+    #[test_case(pgn_sample2(), "? vs ?, C50, 1/2-1/2, ????.01.?? (7 moves)")]
+    #[test_case(pgn_sample6(), "? vs ?, ?, ?, ? (1 move)")]
+    fn summary(sample: PgnSample, summary: &str) {
+        assert_eq!(sample.parsed.unwrap().summary(), summary);
+    }
+
+    #[test]
+    fn summary_without_movetext() {
+        assert_eq!(pgn_sample1().parsed.unwrap().into_headers_only().summary(), "Nasrin_Babayeva vs tigerros0, A00, 0-1, 2024.02.??");
+    }
+
+    #[test_case("tigerros0", true)]
+    #[test_case("TigerRos0", true)]
+    #[test_case("4M9N", true)]
+    #[test_case("tigerros", false)]
+    fn played_by(name: &str, expected: bool) {
+        assert_eq!(pgn_sample0().parsed.unwrap().played_by(name), expected);
+        assert!(!pgn_sample2().parsed.unwrap().played_by(name));
+    }
+
+    #[test]
+    fn has_eco_category() {
+        assert!(pgn_sample0().parsed.unwrap().has_eco_category(EcoCategory::C));
+        assert!(!pgn_sample1().parsed.unwrap().has_eco_category(EcoCategory::C));
+        assert!(!pgn_sample6().parsed.unwrap().has_eco_category(EcoCategory::A));
+    }
+
+    #[test_case(pgn_sample0(), 1490, true)]
+    #[test_case(pgn_sample0(), 1491, false)]
+    #[test_case(pgn_sample1(), 1584, true)]
+    #[test_case(pgn_sample1(), 1600, false)]
+    #[test_case(pgn_sample2(), 0, false; "unknown ratings")]
+    fn rating_at_least(sample: PgnSample, min: u16, expected: bool) {
+        assert_eq!(sample.parsed.unwrap().rating_at_least(min), expected);
+    }
+
+    #[test_case("1. e4 e5 1-0", None, Some(Outcome::Decisive { winner: Color::White }); "movetext only")]
+    #[test_case("1. e4 e5 *", None, Some(Outcome::Other); "unknown")]
+    #[test_case("[Result \"1/2-1/2\"]\n\n1. e4 e5 1-0", Some(Outcome::Draw), Some(Outcome::Decisive { winner: Color::White }); "both")]
+    #[test_case("1. e4 e5", None, None; "no result")]
+    fn movetext_outcome(pgn_str: &str, outcome: Option<Outcome>, movetext_outcome: Option<Outcome>) {
+        let pgn = Pgn::from_str(pgn_str).pop().unwrap().unwrap();
+
+        assert_eq!(pgn.outcome, outcome);
+        assert_eq!(pgn.movetext_outcome, movetext_outcome);
+        assert_eq!(pgn.move_count(), Some(2));
+        assert_eq!(pgn.to_string().trim_start(), pgn_str.replace("1. e4 e5", "1. e4 1... e5"));
+    }
+
+    #[test]
+    fn validate_movetext_outcome() {
+        let missing_header = Pgn::from_str("[Event \"?\"]\n\n1. e4 e5 1-0").pop().unwrap().unwrap();
+
+        assert!(missing_header.validate().contains(&PgnWarning::MissingSevenTagRosterHeader("Result")));
+
+        let disagreeing = Pgn::from_str("[Result \"1/2-1/2\"]\n\n1. e4 e5 1-0").pop().unwrap().unwrap();
+
+        assert!(disagreeing.validate().contains(&PgnWarning::ResultDisagreesWithMovetext {
+            header: Outcome::Draw,
+            movetext: Outcome::Decisive { winner: Color::White },
+        }));
+    }
+
+    #[test]
+    fn escaped_header_round_trip() {
+        let pgn = Pgn {
+            white: Some("Magnus \"The Beast\" Carlsen".to_string()),
+            black: Some("C:\\Users\\tigerros0".to_string()),
+            ..Pgn::default()
+        };
+        let string = pgn.to_string();
+
+        assert_eq!(string, "[White \"Magnus \\\"The Beast\\\" Carlsen\"]\n[Black \"C:\\\\Users\\\\tigerros0\"]\n");
+        assert_eq!(Pgn::from_str(&string).pop().unwrap().unwrap().clone_headers(), pgn);
+    }
+
+    #[test]
+    fn movetext_skeleton() {
+        assert_eq!(pgn_sample1().parsed.unwrap().movetext_skeleton(), vec![
+            (MoveNumber(0), "g4".to_string()),
+            (MoveNumber(1), "e5".to_string()),
+            (MoveNumber(2), "f3".to_string()),
+            (MoveNumber(3), "Qh4#".to_string()),
+        ]);
+        assert_eq!(Pgn::default().movetext_skeleton(), vec![]);
+
+        let fen_pgn = Pgn::from_str("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 20\"]\n\n20... Kd7 21. e4").remove(0).unwrap();
+
+        assert_eq!(fen_pgn.movetext_skeleton(), vec![
+            (MoveNumber(39), "Kd7".to_string()),
+            (MoveNumber(40), "e4".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn result_with_reason() {
+        // The headers of `examples/example.rs`.
+        const PGN: &str = "[White \"Viih_Sou\"]\n[Black \"DanielNaroditsky\"]\n[Result \"1-0\"]\n[Termination \"Viih_Sou won by resignation\"]\n\n1. e4 1-0";
+
+        let pgn = Pgn::from_str(PGN).pop().unwrap().unwrap();
+
+        assert_eq!(pgn.termination.as_deref(), Some("Viih_Sou won by resignation"));
+        assert_eq!(pgn.result_with_reason(), Some((Outcome::Decisive { winner: Color::White }, Some(TerminationReason::Resignation))));
+        assert!(pgn.to_string().contains("[Termination \"Viih_Sou won by resignation\"]\n"));
+        assert_eq!(pgn_sample0().parsed.unwrap().result_with_reason(), Some((Outcome::Decisive { winner: Color::Black }, None)));
+        assert_eq!(Pgn::default().result_with_reason(), None);
+
+        let movetext_result = Pgn::from_str("[Termination \"Viih_Sou won by resignation\"]\n\n1. e4 1-0").pop().unwrap().unwrap();
+
+        assert_eq!(movetext_result.result_with_reason(), Some((Outcome::Decisive { winner: Color::White }, Some(TerminationReason::Resignation))));
+        assert_eq!(Pgn::from_str("1. e4").pop().unwrap().unwrap().result_with_reason(), None);
+    }
+
+    #[test]
+    fn player_and_elo() {
+        let pgn = pgn_sample0().parsed.unwrap();
+
+        assert_eq!(pgn.player(Color::White), Some("4m9n"));
+        assert_eq!(pgn.player(Color::Black), Some("tigerros0"));
+        assert_eq!(pgn.elo(Color::White), Some(1490));
+        assert_eq!(pgn.elo(Color::Black), Some(1565));
+
+        let pgn = pgn_sample2().parsed.unwrap();
+
+        assert_eq!(pgn.player(Color::White), None);
+        assert_eq!(pgn.elo(Color::Black), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_collection() {
+        let sample0 = pgn_sample0();
+        let sample1 = pgn_sample1();
+        let json = serde_json::to_string(&crate::concat_strings!(sample0.string, "\n\n", sample1.string)).unwrap();
+
+        let collection = serde_json::from_str::<PgnCollection>(&json).unwrap();
+
+        assert_eq!(collection, PgnCollection(vec![sample0.parsed.unwrap(), sample1.parsed.unwrap()]));
+        assert!(serde_json::from_str::<Pgn>(&json).is_err());
+        assert_eq!(serde_json::from_str::<PgnCollection>(&serde_json::to_string(&collection).unwrap()).unwrap(), collection);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_single() {
+        let sample = pgn_sample0();
+        let json = serde_json::to_string(sample.string).unwrap();
+
+        assert_eq!(serde_json::from_str::<Pgn>(&json).unwrap(), sample.parsed.unwrap());
+        assert!(serde_json::from_str::<Pgn>("\"\"").is_err());
+    }
 }
\ No newline at end of file
@@ -1,32 +1,74 @@
+use std::cmp::Ordering;
 use std::fmt::{Display, Formatter, Write};
 use std::str::FromStr;
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Default)]
 pub enum Round {
     Normal(u32),
-    Multipart(Vec<u32>),
+    /// A round like `3.1.2`. An unknown part (e.g. the `?` in `3.?.1`) is [`None`].
+    Multipart(Vec<Option<u32>>),
+    /// This is the default.
+    #[default]
     Unknown
 }
 
+impl Round {
+    /// The parts of the round. Empty if this is [`Round::Unknown`].
+    fn parts(&self) -> impl Iterator<Item = Option<u32>> + '_ {
+        let (normal, multipart) = match self {
+            Self::Normal(round) => (Some(Some(*round)), [].as_slice()),
+            Self::Multipart(round_numbers) => (None, round_numbers.as_slice()),
+            Self::Unknown => (None, [].as_slice()),
+        };
+
+        normal.into_iter().chain(multipart.iter().copied())
+    }
+
+    const fn variant_rank(&self) -> u8 {
+        match self {
+            Self::Normal(_) => 0,
+            Self::Multipart(_) => 1,
+            Self::Unknown => 2,
+        }
+    }
+}
+
+/// [`Round::Unknown`] sorts first. Otherwise, rounds are compared part by part,
+/// so `1` < `1.2` < `2`, and an unknown part sorts before a known one, so `1.?` < `1.1`.
+/// A [`Round::Normal`] sorts before an equal one-part [`Round::Multipart`].
+impl Ord for Round {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Unknown, Self::Unknown) => Ordering::Equal,
+            (Self::Unknown, _) => Ordering::Less,
+            (_, Self::Unknown) => Ordering::Greater,
+            _ => self.parts().cmp(other.parts()).then_with(|| self.variant_rank().cmp(&other.variant_rank())),
+        }
+    }
+}
+
+impl PartialOrd for Round {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Display for Round {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Normal(round) => f.write_str(&round.to_string()),
             Self::Multipart(round_numbers) => {
-                for i in 0..round_numbers.len() {
-                    let Some(round_number) = round_numbers.get(i) else {
-                        break;
-                    };
-
-                    f.write_str(&round_number.to_string())?;
-
-                    // CLIPPY: if this for loop executes then round_numbers.len() must be 1 or above
-                    #[allow(clippy::arithmetic_side_effects)]
-                    if i < round_numbers.len() - 1 {
+                for (i, round_number) in round_numbers.iter().enumerate() {
+                    if i != 0 {
                         f.write_char('.')?;
                     }
+
+                    match round_number {
+                        Some(round_number) => f.write_str(&round_number.to_string())?,
+                        None => f.write_char('?')?,
+                    }
                 }
-                
+
                 Ok(())
             },
             Self::Unknown => f.write_char('?'),
@@ -39,9 +81,11 @@ impl FromStr for Round {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.contains('.') {
-            let split = s.split('.').map_while(|char| char.parse::<u32>().ok());
-
-            return Ok(Self::Multipart(split.collect()));
+            // Every part must be a number or `?`, so empty parts (e.g. `"."` or `"1..2"`) are errors too.
+            return s.split('.')
+                .map(|part| if part == "?" { Ok(None) } else { part.parse::<u32>().map(Some).map_err(|_| ()) })
+                .collect::<Result<_, _>>()
+                .map(Self::Multipart);
         }
         
         s.parse().map_or_else(|_| if s == "?" {
@@ -58,15 +102,79 @@ mod tests {
     use super::*;
     use pretty_assertions::{assert_eq};
     use test_case::test_case;
+    use proptest::proptest;
     
     #[test_case(&Round::Normal(79), "79")]
     #[test_case(&Round::Normal(4_294_967_295), "4294967295")]
     #[test_case(&Round::Normal(0), "0")]
-    #[test_case(&Round::Multipart(vec![3, 7, 1]), "3.7.1")]
-    #[test_case(&Round::Multipart(vec![200, 1000, 0, 1]), "200.1000.0.1")]
+    #[test_case(&Round::Multipart(vec![Some(3), Some(7), Some(1)]), "3.7.1")]
+    #[test_case(&Round::Multipart(vec![Some(200), Some(1000), Some(0), Some(1)]), "200.1000.0.1")]
+    #[test_case(&Round::Multipart(vec![Some(3), None, Some(1)]), "3.?.1")]
+    #[test_case(&Round::Multipart(vec![None, None]), "?.?")]
     #[test_case(&Round::Unknown, "?")]
     fn to_string_from_string(round: &Round, round_str: &str) {
         assert_eq!(round.to_string(), round_str);
         assert_eq!(&Round::from_str(round_str).unwrap(), round);
     }
+
+    #[test]
+    fn ord() {
+        let mut rounds = vec![
+            Round::Normal(2),
+            Round::Multipart(vec![Some(1), Some(2)]),
+            Round::Unknown,
+            Round::Multipart(vec![Some(1)]),
+            Round::Multipart(vec![Some(1), None]),
+            Round::Normal(1),
+        ];
+
+        rounds.sort();
+
+        assert_eq!(rounds, vec![
+            Round::Unknown,
+            Round::Normal(1),
+            Round::Multipart(vec![Some(1)]),
+            Round::Multipart(vec![Some(1), None]),
+            Round::Multipart(vec![Some(1), Some(2)]),
+            Round::Normal(2),
+        ]);
+    }
+
+    #[test]
+    fn default() {
+        assert_eq!(Round::default(), Round::Unknown);
+        assert_eq!(Round::default().to_string(), "?");
+    }
+
+    #[test_case("")]
+    #[test_case(".")]
+    #[test_case("..")]
+    #[test_case("1.")]
+    #[test_case(".1")]
+    #[test_case("1..2")]
+    #[test_case("3.??.1")]
+    #[test_case("3.x.1")]
+    #[test_case("4294967296")]
+    #[test_case("1.4294967296")]
+    #[test_case("-1")]
+    #[test_case("٣")]
+    fn from_invalid_string(round_str: &str) {
+        assert_eq!(Round::from_str(round_str), Err(()));
+    }
+
+    #[test]
+    fn from_long_string() {
+        let long_multipart = vec!["1"; 10_000].join(".");
+
+        assert_eq!(Round::from_str(&long_multipart), Ok(Round::Multipart(vec![Some(1); 10_000])));
+        assert_eq!(Round::from_str(&"9".repeat(10_000)), Err(()));
+        assert_eq!(Round::from_str(&".".repeat(10_000)), Err(()));
+    }
+
+    proptest! {
+        #[test]
+        fn never_panics(round_str in "\\PC*") {
+            let _ = Round::from_str(&round_str);
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,93 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Why a game ended, parsed from the `Termination` header.
+///
+/// The standard values (e.g. `time forfeit`) as well as prose used by some sites
+/// (e.g. `Viih_Sou won by resignation`) are understood.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TerminationReason {
+    Checkmate,
+    Resignation,
+    TimeForfeit,
+    Stalemate,
+    Agreement,
+    Repetition,
+    InsufficientMaterial,
+    Abandoned,
+    Adjudication,
+}
+
+impl Display for TerminationReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Checkmate => "checkmate",
+            Self::Resignation => "resignation",
+            Self::TimeForfeit => "time forfeit",
+            Self::Stalemate => "stalemate",
+            Self::Agreement => "agreement",
+            Self::Repetition => "repetition",
+            Self::InsufficientMaterial => "insufficient material",
+            Self::Abandoned => "abandoned",
+            Self::Adjudication => "adjudication",
+        })
+    }
+}
+
+/// Looks for a keyword in the string, ignoring case.
+///
+/// Errors if there's none, e.g. for the standard `normal` value, which doesn't say why the game ended.
+impl FromStr for TerminationReason {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+
+        [
+            ("checkmate", Self::Checkmate),
+            ("resign", Self::Resignation),
+            // Before the time keywords, because of e.g. `Game drawn by timeout vs insufficient material`.
+            ("stalemate", Self::Stalemate),
+            ("agree", Self::Agreement),
+            ("repetition", Self::Repetition),
+            ("insufficient material", Self::InsufficientMaterial),
+            ("time forfeit", Self::TimeForfeit),
+            ("on time", Self::TimeForfeit),
+            ("timeout", Self::TimeForfeit),
+            ("abandon", Self::Abandoned),
+            ("adjudicat", Self::Adjudication),
+        ].into_iter().find_map(|(keyword, reason)| s.contains(keyword).then_some(reason)).ok_or(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case("Viih_Sou won by resignation", TerminationReason::Resignation)]
+    #[test_case("time forfeit", TerminationReason::TimeForfeit)]
+    #[test_case("Time forfeit", TerminationReason::TimeForfeit)]
+    #[test_case("tigerros0 won on time", TerminationReason::TimeForfeit)]
+    #[test_case("4m9n won by checkmate", TerminationReason::Checkmate)]
+    #[test_case("Game drawn by stalemate", TerminationReason::Stalemate)]
+    #[test_case("Game drawn by agreement", TerminationReason::Agreement)]
+    #[test_case("Game drawn by repetition", TerminationReason::Repetition)]
+    #[test_case("Game drawn by insufficient material", TerminationReason::InsufficientMaterial)]
+    #[test_case("Game drawn by timeout vs insufficient material", TerminationReason::InsufficientMaterial)]
+    #[test_case("Black won by timeout", TerminationReason::TimeForfeit)]
+    #[test_case("abandoned", TerminationReason::Abandoned)]
+    #[test_case("adjudication", TerminationReason::Adjudication)]
+    fn from_str(termination: &str, reason: TerminationReason) {
+        assert_eq!(TerminationReason::from_str(termination), Ok(reason));
+    }
+
+    #[test_case("")]
+    #[test_case("normal")]
+    #[test_case("time control")]
+    #[test_case("unterminated")]
+    fn from_invalid_str(termination: &str) {
+        assert_eq!(TerminationReason::from_str(termination), Err(()));
+    }
+}
@@ -1,13 +1,16 @@
 use pgn_reader::{RawHeader, Skip};
+use std::borrow::Cow;
 use std::str::FromStr;
 use shakmaty::Chess;
 use shakmaty::san::SanPlus;
-use crate::{Variation, Eco, pgn::{Date, Round, Outcome, Pgn}, TurnsCapacity, VariationsCapacity, VariationSanPlayError};
+use super::pgn::position_from_fen;
+use crate::{Variation, Eco, pgn::{Date, Round, Outcome, Pgn, RootPositionError}, TurnsCapacity, VariationsCapacity, VariationSanPlayError};
 
 pub(super) struct Visitor {
     event: Option<String>,
     site: Option<String>,
     date: Option<Date>,
+    event_date: Option<Date>,
     round: Option<Round>,
     white: Option<String>,
     white_elo: Option<u16>,
@@ -15,7 +18,14 @@ pub(super) struct Visitor {
     black_elo: Option<u16>,
     outcome: Option<Outcome>,
     eco: Option<Eco>,
+    opening: Option<String>,
+    opening_variation: Option<String>,
     time_control: Option<String>,
+    termination: Option<String>,
+    set_up: Option<bool>,
+    movetext_outcome: Option<Outcome>,
+    fen: Option<String>,
+    root_position_error: Option<RootPositionError>,
     variation_tree: Vec<(usize, Variation)>,
     current_turn_index: usize,
     root_variation: Variation,
@@ -28,6 +38,7 @@ impl Visitor {
             event: None,
             site: None,
             date: None,
+            event_date: None,
             round: None,
             white: None,
             white_elo: None,
@@ -35,7 +46,14 @@ impl Visitor {
             black_elo: None,
             outcome: None,
             eco: None,
+            opening: None,
+            opening_variation: None,
             time_control: None,
+            termination: None,
+            set_up: None,
+            movetext_outcome: None,
+            fen: None,
+            root_position_error: None,
             variation_tree: Vec::with_capacity(0),
             current_turn_index: 0,
             root_variation: Variation::new(Chess::default(), TurnsCapacity::default()),
@@ -57,6 +75,7 @@ impl Visitor {
             event: self.event,
             site: self.site,
             date: self.date,
+            event_date: self.event_date,
             white: self.white,
             white_elo: self.white_elo,
             black: self.black,
@@ -64,32 +83,77 @@ impl Visitor {
             outcome: self.outcome,
             round: self.round,
             eco: self.eco,
+            opening: self.opening,
+            opening_variation: self.opening_variation,
             time_control: self.time_control,
-            root_variation: Some(self.root_variation),
+            termination: self.termination,
+            set_up: self.set_up,
+            movetext_outcome: self.movetext_outcome,
+            fen: self.fen,
+            root_variation: self.root_position_error.is_none().then_some(self.root_variation),
+            root_position_error: self.root_position_error,
         })
     }
 }
 
+/// Decodes the value as UTF-8, or as Latin-1 if it isn't valid UTF-8. Every byte is a Latin-1 character, so nothing is lost.
+fn decode_header_value(value: RawHeader<'_>) -> String {
+    value.decode_utf8().map_or_else(|_| value.decode().iter().copied().map(char::from).collect(), Cow::into_owned)
+}
+
 impl pgn_reader::Visitor for Visitor {
     type Result = ();
 
+    /// If a header occurs more than once, the last value wins.
     fn header(&mut self, key: &[u8], value: RawHeader<'_>) {
         match key.to_ascii_lowercase().as_slice() {
-            b"event" => self.event = Some(value.decode_utf8_lossy().to_string()),
-            b"site" => self.site = Some(value.decode_utf8_lossy().to_string()),
+            b"event" => self.event = Some(decode_header_value(value)),
+            b"site" => self.site = Some(decode_header_value(value)),
             b"date" => self.date = Date::from_str(&value.decode_utf8_lossy()).ok(),
-            b"white" => self.white = Some(value.decode_utf8_lossy().to_string()),
-            b"black" => self.black = Some(value.decode_utf8_lossy().to_string()),
+            b"eventdate" => self.event_date = Date::from_str(&value.decode_utf8_lossy()).ok(),
+            b"white" => self.white = Some(decode_header_value(value)),
+            b"black" => self.black = Some(decode_header_value(value)),
             b"whiteelo" => self.white_elo = value.decode_utf8_lossy().parse().ok(),
             b"blackelo" => self.black_elo = value.decode_utf8_lossy().parse().ok(),
             b"result" => self.outcome = Outcome::from_str(&value.decode_utf8_lossy()).ok(),
             b"round" => self.round = Round::from_str(&value.decode_utf8_lossy()).ok(),
-            b"eco" => self.eco = Eco::from_str(&value.decode_utf8_lossy()).ok(),
-            b"timecontrol" => self.time_control = Some(value.decode_utf8_lossy().to_string()),
+            b"eco" => {
+                let value = value.decode_utf8_lossy();
+
+                // `Eco::from_str` ignores anything after the code, so values like `C50 ` still parse, just without a subvariant.
+                self.eco = Eco::from_str_extended(&value).or_else(|_| Eco::from_str(&value)).ok();
+            },
+            b"opening" => self.opening = Some(decode_header_value(value)),
+            b"variation" => self.opening_variation = Some(decode_header_value(value)),
+            b"timecontrol" => self.time_control = Some(decode_header_value(value)),
+            b"termination" => self.termination = Some(decode_header_value(value)),
+            b"setup" => self.set_up = match &*value.decode_utf8_lossy() {
+                "1" => Some(true),
+                "0" => Some(false),
+                _ => None,
+            },
+            b"fen" => self.fen = Some(decode_header_value(value)),
             _ => {},
         }
     }
 
+    fn end_headers(&mut self) -> Skip {
+        let Some(fen) = &self.fen else {
+            return Skip(false);
+        };
+
+        match position_from_fen(fen) {
+            Ok(position) => {
+                self.root_variation = Variation::new(position, TurnsCapacity::default());
+                Skip(false)
+            },
+            Err(error) => {
+                self.root_position_error = Some(error);
+                Skip(true)
+            },
+        }
+    }
+
     fn begin_variation(&mut self) -> Skip {
         if self.result.is_err() {
             return Skip(true);
@@ -149,7 +213,6 @@ impl pgn_reader::Visitor for Visitor {
         let current_variation = self.variation_tree.last_mut().map(|pair| &mut pair.1).unwrap_or(&mut self.root_variation);
 
         //println!("Current variation position: \n{:?}", current_variation.last_position().board());
-
         if let Err(error) = current_variation.play_san(&san_plus.san, VariationsCapacity::default()) {
             //println!("Move {} is err", san_plus.san);
             self.result = Err(VariationSanPlayError {
@@ -168,5 +231,9 @@ impl pgn_reader::Visitor for Visitor {
         }
     }
 
+    fn outcome(&mut self, outcome: Option<shakmaty::Outcome>) {
+        self.movetext_outcome = Some(outcome.map_or(Outcome::Other, Outcome::from));
+    }
+
     fn end_game(&mut self) -> Self::Result {}
 }
\ No newline at end of file
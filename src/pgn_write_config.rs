@@ -0,0 +1,33 @@
+/// Options for writing movetext.
+///
+/// The [`Default`] is what the [`Display`](std::fmt::Display) implementations use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PgnWriteConfig {
+    /// Whether to write the move number of a black move that directly follows white's move,
+    /// e.g. `1. e4 1... e5` instead of `1. e4 e5`.
+    ///
+    /// A black move that starts a variation or follows a variation always has its number written.
+    ///
+    /// Default: `true`.
+    pub explicit_black_numbers: bool,
+    /// The minimum width of move numbers, which are padded with spaces on the left,
+    /// e.g. ` 1. e4` and `10. e4` with a width of 2. Useful for aligning monospace output.
+    ///
+    /// Default: [`None`] (no padding).
+    pub number_width: Option<usize>,
+    /// Whether to write the piece letter at the start of a SAN as a figurine, e.g. `♘f3` instead of `Nf3`.
+    /// Promotions are still written with letters, e.g. `e8=Q`. Meant for display only, parsing expects letters.
+    ///
+    /// Default: `false`.
+    pub figurine: bool,
+}
+
+impl Default for PgnWriteConfig {
+    fn default() -> Self {
+        Self {
+            explicit_black_numbers: true,
+            number_width: None,
+            figurine: false,
+        }
+    }
+}
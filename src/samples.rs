@@ -105,6 +105,7 @@ pub fn pgn_sample0() -> PgnSample {
                 )
                 .unwrap(),
             ),
+            event_date: None,
             white: Some("4m9n".to_string()),
             black: Some("tigerros0".to_string()),
             outcome: Some(Outcome::Decisive {
@@ -114,8 +115,15 @@ pub fn pgn_sample0() -> PgnSample {
             white_elo: Some(1490),
             black_elo: Some(1565),
             eco: Some(Eco::new(EcoCategory::C, 50).unwrap()),
+            opening: None,
+            opening_variation: None,
             time_control: Some("600+0".to_string()),
+            termination: None,
+            set_up: None,
+            fen: None,
+            root_position_error: None,
             root_variation: Some(variation_sample0()),
+            movetext_outcome: None,
         }),
     )
 }
@@ -157,6 +165,7 @@ pub fn pgn_sample1() -> PgnSample {
                 )
                 .unwrap(),
             ),
+            event_date: None,
             white: Some("Nasrin_Babayeva".to_string()),
             white_elo: Some(1765),
             black: Some("tigerros0".to_string()),
@@ -164,10 +173,17 @@ pub fn pgn_sample1() -> PgnSample {
             outcome: Some(Outcome::Decisive {
                 winner: Color::Black,
             }),
-            round: Some(Round::Multipart(vec![3, 1, 2])),
+            round: Some(Round::Multipart(vec![Some(3), Some(1), Some(2)])),
             eco: Some(Eco::new(EcoCategory::A, 00).unwrap()),
+            opening: None,
+            opening_variation: None,
             time_control: Some("600+2".to_string()),
+            termination: None,
+            set_up: None,
+            fen: None,
+            root_position_error: None,
             root_variation: Some(variation_sample1()),
+            movetext_outcome: None,
         }),
     )
 }
@@ -237,6 +253,7 @@ pub fn pgn_sample2() -> PgnSample {
             date: Some(
                 Date::new(None, Some(unsafe { NonZeroU8::new_unchecked(1) }), None).unwrap(),
             ),
+            event_date: None,
             white: None,
             black: None,
             outcome: Some(Outcome::Draw),
@@ -244,8 +261,15 @@ pub fn pgn_sample2() -> PgnSample {
             white_elo: None,
             black_elo: None,
             eco: Some(Eco::new(EcoCategory::C, 50).unwrap()),
+            opening: None,
+            opening_variation: None,
             time_control: None,
+            termination: None,
+            set_up: None,
+            fen: None,
+            root_position_error: None,
             root_variation: Some(variation_sample2()),
+            movetext_outcome: None,
         }),
     )
 }
@@ -311,14 +335,22 @@ pub fn pgn_sample6() -> PgnSample {
             site: None,
             date: None,
             round: None,
+            event_date: None,
             white: None,
             white_elo: None,
             black: None,
             black_elo: None,
             outcome: None,
             eco: None,
+            opening: None,
+            opening_variation: None,
             time_control: None,
+            termination: None,
+            set_up: None,
+            fen: None,
+            root_position_error: None,
             root_variation: Some(variation_sample6()),
+            movetext_outcome: None,
         }),
     )
 }
@@ -1,5 +1,6 @@
 use std::fmt::{Display, Formatter, Write};
 use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum TimeControlField {
@@ -25,6 +26,49 @@ pub enum TimeControlFieldParseError {
     IncrementIncrementSecondsParseError,
 }
 
+impl TimeControlField {
+    /// Returns the base time of this time control,
+    /// or [`None`] if it's [`TimeControlField::Unknown`] or [`TimeControlField::NoTimeControl`].
+    ///
+    /// For [`TimeControlField::MovesPerSeconds`], this is the time for the moves.
+    /// For [`TimeControlField::Hourglass`], this is the time of the hourglass.
+    pub fn base_duration(&self) -> Option<Duration> {
+        match *self {
+            Self::Unknown | Self::NoTimeControl => None,
+            Self::MovesPerSeconds { seconds, .. }
+            | Self::Seconds(seconds)
+            | Self::Increment { seconds, .. }
+            | Self::Hourglass { seconds } => Some(Duration::from_secs(u64::from(seconds))),
+        }
+    }
+
+    /// Returns the increment of a [`TimeControlField::Increment`], or [`None`] for any other variant.
+    pub fn increment_duration(&self) -> Option<Duration> {
+        match *self {
+            Self::Increment { increment_seconds, .. } => Some(Duration::from_secs(u64::from(increment_seconds))),
+            _ => None,
+        }
+    }
+
+    /// Collapses an [`TimeControlField::Increment`] with a zero increment into [`TimeControlField::Seconds`].
+    /// Any other value is returned unchanged.
+    ///
+    /// Parsing and displaying are exact, so this is only applied when called explicitly.
+    #[must_use]
+    pub const fn normalized(self) -> Self {
+        match self {
+            Self::Increment { seconds, increment_seconds: 0 } => Self::Seconds(seconds),
+            _ => self,
+        }
+    }
+
+    /// Compares the [normalized](Self::normalized) forms of `self` and `other`,
+    /// so that `600` and `600+0` are considered equal.
+    pub fn eq_normalized(self, other: Self) -> bool {
+        self.normalized() == other.normalized()
+    }
+}
+
 impl FromStr for TimeControlField {
     type Err = TimeControlFieldParseError;
 
@@ -108,6 +152,36 @@ mod tests {
         assert_eq!(TimeControlField::from_str(time_control_str).unwrap(), time_control);
     }
 
+    #[test_case(TimeControlField::Unknown, None, None)]
+    #[test_case(TimeControlField::NoTimeControl, None, None)]
+    #[test_case(TimeControlField::MovesPerSeconds { moves: 40, seconds: 9000 }, Some(9000), None)]
+    #[test_case(TimeControlField::Seconds(600), Some(600), None)]
+    #[test_case(TimeControlField::Increment { seconds: 600, increment_seconds: 10 }, Some(600), Some(10))]
+    #[test_case(TimeControlField::Hourglass { seconds: 1000 }, Some(1000), None)]
+    fn durations(time_control: TimeControlField, base_seconds: Option<u64>, increment_seconds: Option<u64>) {
+        assert_eq!(time_control.base_duration(), base_seconds.map(Duration::from_secs));
+        assert_eq!(time_control.increment_duration(), increment_seconds.map(Duration::from_secs));
+    }
+
+    #[test_case(TimeControlField::Increment { seconds: 600, increment_seconds: 0 }, TimeControlField::Seconds(600))]
+    #[test_case(TimeControlField::Increment { seconds: 600, increment_seconds: 5 }, TimeControlField::Increment { seconds: 600, increment_seconds: 5 })]
+    #[test_case(TimeControlField::Seconds(600), TimeControlField::Seconds(600))]
+    #[test_case(TimeControlField::Unknown, TimeControlField::Unknown)]
+    fn normalized(time_control: TimeControlField, expected: TimeControlField) {
+        assert_eq!(time_control.normalized(), expected);
+    }
+
+    #[test]
+    fn eq_normalized() {
+        let seconds = TimeControlField::from_str("600").unwrap();
+        let zero_increment = TimeControlField::from_str("600+0").unwrap();
+
+        assert_ne!(seconds, zero_increment);
+        assert!(seconds.eq_normalized(zero_increment));
+        assert!(!seconds.eq_normalized(TimeControlField::from_str("600+1").unwrap()));
+        assert_eq!(zero_increment.to_string(), "600+0");
+    }
+
     proptest! {
         #[test]
         fn moves_per_seconds(moves: u32, seconds: u32) {
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
 use std::fmt::{Debug, Display, Formatter, Write};
-use shakmaty::{Chess, Move, Position};
+use std::num::NonZeroUsize;
+use shakmaty::{Bitboard, Board, CastlingSide, Chess, Color, EnPassantMode, Move, Position, Role, Square};
+use shakmaty::fen::Fen;
 use shakmaty::san::{San, SanError, SanPlus, Suffix};
+use shakmaty::uci::{IllegalUciError, Uci};
 
 #[derive(Debug, Clone, Copy)]
 pub struct VariationsCapacity(pub usize);
@@ -44,6 +48,48 @@ impl Turn {
     pub fn get_variation_mut(&mut self, index: usize) -> Option<&mut Variation> {
         self.variations.get_mut(index)
     }
+
+    /// Returns the side this turn's move castles to, or [`None`] if it isn't castling.
+    pub fn castling_side(&self) -> Option<CastlingSide> {
+        self.r#move.castling_side()
+    }
+
+    /// Returns `true` if this turn's move is an en passant capture.
+    pub fn is_en_passant(&self) -> bool {
+        self.r#move.is_en_passant()
+    }
+
+    /// Returns the role this turn's move promotes to, or [`None`] if it isn't a promotion.
+    pub fn promotion(&self) -> Option<Role> {
+        self.r#move.promotion()
+    }
+
+    /// Returns the amount of pieces (including kings and pawns) on the board after this turn's move.
+    pub fn piece_count_after(&self) -> usize {
+        self.position_after.board().occupied().count()
+    }
+
+    /// The amount of alternatives to this turn's move. Same as `variations().len()`.
+    pub fn variation_count(&self) -> usize {
+        self.variations.len()
+    }
+
+    /// Removes variations that are identical to an earlier sibling, keeping the first occurrence.
+    ///
+    /// Nested duplicates are removed first, so variations that only differed by duplicates become identical too.
+    pub fn dedup_variations(&mut self) {
+        let mut unique: Vec<Variation> = Vec::with_capacity(self.variations.len());
+
+        for mut variation in std::mem::take(&mut self.variations) {
+            variation.dedup_all();
+
+            if !unique.contains(&variation) {
+                unique.push(variation);
+            }
+        }
+
+        self.variations = unique;
+    }
 }
 
 /// An always legal variation with a history of [`Turn`]s.
@@ -52,6 +98,11 @@ impl Turn {
 /// *before* the move at turn index `i` is played.
 /// To get the position *after* the last move was played, you can use [`Variation::position_after_last_move`],
 /// or `Variation::get_position(Variation::turns.len())`.
+///
+/// Subvariations are identified by *paths*: lists of indexes alternating between a turn index and a variation index,
+/// i.e. `[turn_index, variation_index, turn_index, variation_index, ..]`.
+/// For example, `[0, 1]` is the second variation of the first turn of this variation.
+/// The empty path refers to this variation.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Variation {
     first_position: Chess,
@@ -82,6 +133,41 @@ pub enum PlaySanAtError {
     PlayError(VariationSanPlayError)
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum InsertMoveError {
+    NoTurnAt { index: usize },
+    /// One of the inserted moves is illegal.
+    PlayError(VariationPlayError),
+    /// After the insertion, the move at `turn_index` (or one of its subvariations) is no longer legal.
+    /// The index is that of the turn before the insertion.
+    TailBecameIllegal { turn_index: usize },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReplaceMoveError {
+    NoTurnAt { index: usize },
+    /// The new move is illegal.
+    PlayError(VariationPlayError),
+    /// After the replacement, the move at `turn_index` (or one of its subvariations) is no longer legal.
+    TailBecameIllegal { turn_index: usize },
+}
+
+/// The move at `turn_index` (or one of its subvariations) is not legal on the new first position.
+///
+/// See [`Variation::rebase`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RebaseError {
+    pub turn_index: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PromoteError {
+    /// The path doesn't point to a subvariation.
+    NoSuchVariation,
+    /// The subvariation has no turns, so it can't replace the mainline.
+    EmptyVariation,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct NoSuchTurnError {
     pub index: usize
@@ -100,9 +186,54 @@ pub struct VariationSanPlayError {
     pub error: SanError
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub struct VariationUciPlayError {
+    pub turn_index: usize,
+    pub uci: Uci,
+    pub error: IllegalUciError
+}
+
+/// A position in the tree returned by [`Variation::fen_tree`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FenNode {
+    pub fen: String,
+    /// The positions after each move that can be played in this position.
+    /// The mainline continuation comes first, followed by the alternatives in variation order.
+    pub children: Vec<FenNode>,
+}
+
+/// What a SAN means in the last position of a variation. See [`Variation::classify_san`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SanClassification {
+    Legal(Move),
+    /// The SAN matches more than one legal move. Contains all of them.
+    Ambiguous(Vec<Move>),
+    Illegal,
+}
+
+/// Whether [`Variation::upsert_variation`] added the variation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UpsertResult {
+    Added,
+    /// An identical variation already existed, so nothing was changed.
+    AlreadyPresent,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct TurnsCapacity(pub usize);
 
+/// Allocation statistics of a [`Variation`], summed over all subvariations.
+///
+/// See [`Variation::capacity_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CapacityStats {
+    pub turns_len: usize,
+    pub turns_capacity: usize,
+    pub variations_len: usize,
+    pub variations_capacity: usize,
+}
+
 impl Default for TurnsCapacity {
     /// 100
     fn default() -> Self {
@@ -118,10 +249,33 @@ impl Variation {
         }
     }
 
+    /// Parses movetext without headers, e.g. `1. e4 e5 (1... c5) 2. Nf3`, from the standard starting position.
+    ///
+    /// Only the first game is read. An empty string results in an empty variation.
+    ///
+    /// # Errors
+    ///
+    /// See [`PgnParseError`].
+    pub fn from_movetext_str(movetext: &str) -> Result<Self, PgnParseError> {
+        let Some(pgn) = Pgn::from_str(movetext).into_iter().next() else {
+            return Ok(Self::new(Chess::default(), TurnsCapacity::default()));
+        };
+
+        Ok(pgn?.root_variation.unwrap_or_else(|| Self::new(Chess::default(), TurnsCapacity::default())))
+    }
+
     pub const fn first_position(&self) -> &Chess {
         &self.first_position
     }
 
+    /// Returns the move number of the first move, derived from the side to move and fullmove number
+    /// of [`Self::first_position`], e.g. `20...` for a first position with the FEN `4k3/8/8/8/8/8/4P3/4K3 b - - 0 20`.
+    pub fn first_move_number(&self) -> MoveNumber {
+        let number = NonZeroUsize::try_from(self.first_position.fullmoves()).unwrap_or(NonZeroUsize::MIN);
+
+        MoveNumber::from_color_and_number(self.first_position.turn(), number)
+    }
+
     pub const fn turns(&self) -> &Vec<Turn> {
         &self.turns
     }
@@ -156,6 +310,186 @@ impl Variation {
         }
     }
 
+    /// Returns the position that occurs *after* the turn at `index` is played,
+    /// or [`None`] if the index is out of bounds.
+    ///
+    /// For an in-bounds `index`, this is the same as `get_position(index + 1)`.
+    ///
+    /// See also [`Self::get_position`] and [`Self::position_after_last_move`].
+    pub fn position_after(&self, index: usize) -> Option<&Chess> {
+        self.turns.get(index).map(Turn::position_after)
+    }
+
+    /// Returns the side to move before the turn at `index` is played,
+    /// or [`None`] if the index is out of bounds. See [`Self::get_position`].
+    ///
+    /// This is derived from the position rather than the index, so it's correct for a first position where black moves first.
+    pub fn side_to_move_at(&self, index: usize) -> Option<Color> {
+        self.get_position(index).map(Position::turn)
+    }
+
+    /// Returns the amount of pieces (including kings and pawns) on the board before the turn at `index` is played,
+    /// or [`None`] if the index is out of bounds. See [`Self::get_position`].
+    pub fn piece_count_at(&self, index: usize) -> Option<usize> {
+        self.get_position(index).map(|position| position.board().occupied().count())
+    }
+
+    /// Returns the correctly suffixed SAN of the move at turn `index`,
+    /// or [`None`] if the index is out of bounds.
+    ///
+    /// See also [`san_plus_from_move`].
+    pub fn get_san_plus(&self, index: usize) -> Option<SanPlus> {
+        let turn = self.turns.get(index)?;
+        let position_before = self.get_position(index)?;
+
+        Some(SanPlus {
+            san: San::from_move(position_before, &turn.r#move),
+            suffix: Suffix::from_position(&turn.position_after),
+        })
+    }
+
+    /// Returns the path of the first turn whose move is `san`, or [`None`] if there's no such turn.
+    ///
+    /// The path is the path of the variation containing the turn, followed by the turn index.
+    /// A SAN matches if it resolves to the turn's move in the position before it,
+    /// so the disambiguation doesn't have to be minimal (e.g. `Ngf3` matches `Nf3`).
+    ///
+    /// Turns are searched in the order they appear in PGN movetext:
+    /// each turn is checked before its subvariations, and those before the next turn.
+    pub fn find_san(&self, san: &San) -> Option<Vec<usize>> {
+        for (turn_i, turn) in self.turns.iter().enumerate() {
+            let position_before = self.get_position(turn_i)?;
+
+            if san.to_move(position_before).is_ok_and(|r#move| r#move == turn.r#move) {
+                return Some(vec![turn_i]);
+            }
+
+            for (variation_i, subvariation) in turn.variations.iter().enumerate() {
+                if let Some(subpath) = subvariation.find_san(san) {
+                    let mut path = Vec::with_capacity(subpath.len().saturating_add(2));
+
+                    path.push(turn_i);
+                    path.push(variation_i);
+                    path.extend(subpath);
+
+                    return Some(path);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns the subvariation at the given path, or [`None`] if it doesn't exist.
+    ///
+    /// See the [`Variation`] docs for what a path is.
+    pub fn get_variation_by_path(&self, path: &[usize]) -> Option<&Self> {
+        if path.len() % 2 != 0 {
+            return None;
+        }
+
+        let mut variation = self;
+
+        for pair in path.chunks_exact(2) {
+            let &[turn_index, variation_index] = pair else {
+                return None;
+            };
+
+            variation = variation.turns.get(turn_index)?.variations.get(variation_index)?;
+        }
+
+        Some(variation)
+    }
+
+    /// See [`Self::get_variation_by_path`].
+    pub fn get_variation_by_path_mut(&mut self, path: &[usize]) -> Option<&mut Self> {
+        if path.len() % 2 != 0 {
+            return None;
+        }
+
+        let mut variation = self;
+
+        for pair in path.chunks_exact(2) {
+            let &[turn_index, variation_index] = pair else {
+                return None;
+            };
+
+            variation = variation.turns.get_mut(turn_index)?.variations.get_mut(variation_index)?;
+        }
+
+        Some(variation)
+    }
+
+    /// Makes the subvariation at `path` the mainline of its parent variation.
+    ///
+    /// The replaced mainline (starting at the turn the subvariation is anchored at) becomes a subvariation
+    /// in place of the promoted one, so promoting the same path again swaps them back.
+    /// The other alternatives of the anchor turn are kept. The alternatives to the first move of the
+    /// promoted subvariation are appended after them, since they start at the same position.
+    ///
+    /// See the [`Variation`] docs for what a path is.
+    ///
+    /// # Errors
+    ///
+    /// See [`PromoteError`].
+    pub fn promote_path(&mut self, path: &[usize]) -> Result<(), PromoteError> {
+        let parent_path_len = path.len().checked_sub(2).ok_or(PromoteError::NoSuchVariation)?;
+        let (parent_path, &[turn_index, variation_index]) = path.split_at(parent_path_len) else {
+            return Err(PromoteError::NoSuchVariation);
+        };
+        let parent = self.get_variation_by_path_mut(parent_path).ok_or(PromoteError::NoSuchVariation)?;
+        let promoted = parent.turns
+            .get(turn_index)
+            .and_then(|turn| turn.variations.get(variation_index))
+            .ok_or(PromoteError::NoSuchVariation)?;
+
+        if promoted.turns.is_empty() {
+            return Err(PromoteError::EmptyVariation);
+        }
+
+        let first_position = promoted.first_position.clone();
+        let mut demoted_turns = parent.turns.split_off(turn_index);
+        let mut alternatives = demoted_turns.first_mut().map(|turn| std::mem::take(&mut turn.variations)).unwrap_or_default();
+        let mut promoted = alternatives.remove(variation_index);
+        let mut promoted_alternatives = promoted.turns.first_mut().map(|turn| std::mem::take(&mut turn.variations)).unwrap_or_default();
+
+        alternatives.insert(variation_index, Self {
+            first_position,
+            turns: demoted_turns,
+        });
+        alternatives.append(&mut promoted_alternatives);
+
+        if let Some(first_turn) = promoted.turns.first_mut() {
+            first_turn.variations = alternatives;
+        }
+
+        parent.turns.append(&mut promoted.turns);
+
+        Ok(())
+    }
+
+    /// Returns the path to the next alternative of the subvariation at `path`,
+    /// or [`None`] if there is no such subvariation.
+    pub fn next_sibling_path(&self, path: &[usize]) -> Option<Vec<usize>> {
+        let mut sibling_path = path.to_vec();
+        let variation_index = sibling_path.last_mut()?;
+
+        *variation_index = variation_index.checked_add(1)?;
+
+        self.get_variation_by_path(&sibling_path).map(|_| sibling_path)
+    }
+
+    /// Returns the path to the previous alternative of the subvariation at `path`,
+    /// or [`None`] if there is no such subvariation.
+    pub fn prev_sibling_path(&self, path: &[usize]) -> Option<Vec<usize>> {
+        let mut sibling_path = path.to_vec();
+        let variation_index = sibling_path.last_mut()?;
+
+        *variation_index = variation_index.checked_sub(1)?;
+
+        self.get_variation_by_path(&sibling_path).map(|_| sibling_path)
+    }
+
     /// Equivalent to [`Vec::get_mut`].
     ///
     /// Useful if you need to modify a variation at that turn.
@@ -207,6 +541,57 @@ impl Variation {
         Ok(())
     }
 
+    /// Same as [`Self::play_san`], but with a UCI move, like the ones engines output.
+    ///
+    /// # Errors
+    ///
+    /// See [`VariationUciPlayError`]. `turn_index` is set to the index the move would have had.
+    pub fn play_uci(&mut self, uci: &Uci, variations_capacity: VariationsCapacity) -> Result<(), VariationUciPlayError> {
+        let position_after_last_move = self.position_after_last_move();
+        let r#move = uci.to_move(position_after_last_move).map_err(|error| VariationUciPlayError {
+            turn_index: self.turns.len(),
+            uci: uci.clone(),
+            error,
+        })?;
+
+        let mut new_position = position_after_last_move.clone();
+        new_position.play_unchecked(&r#move);
+
+        self.turns.push(Turn::new(r#move, variations_capacity, new_position));
+
+        Ok(())
+    }
+
+    /// Determines whether `san` can be played in the last position,
+    /// listing the candidate moves if it's ambiguous so that the user can be asked to disambiguate.
+    pub fn classify_san(&self, san: &San) -> SanClassification {
+        let position = self.position_after_last_move();
+
+        match san.to_move(position) {
+            Ok(r#move) => SanClassification::Legal(r#move),
+            Err(SanError::AmbiguousSan) => SanClassification::Ambiguous(
+                position.legal_moves().into_iter().filter(|r#move| san.matches(r#move)).collect()
+            ),
+            Err(SanError::IllegalSan) => SanClassification::Illegal,
+        }
+    }
+
+    /// Plays every legal SAN in the last position, skipping the illegal/ambiguous ones.
+    ///
+    /// Unlike [`play_sans!`], this doesn't stop at the first error.
+    /// Returns the errors along with the index of the SAN in `sans` that caused it.
+    pub fn try_extend_sans(&mut self, sans: impl IntoIterator<Item = San>) -> Vec<(usize, SanError)> {
+        let mut errors = Vec::new();
+
+        for (san_i, san) in sans.into_iter().enumerate() {
+            if let Err(error) = self.play_san(&san, VariationsCapacity::default()) {
+                errors.push((san_i, error.error));
+            }
+        }
+
+        errors
+    }
+
     // CLIPPY: All potential panicking code is explained.
     #[allow(clippy::missing_panics_doc)]
     /// Attempts to play a move at the specified index,
@@ -241,6 +626,47 @@ impl Variation {
         Ok(())
     }
 
+    /// Replaces the move at `index`, keeping the turns after it if they're still legal.
+    ///
+    /// Unlike [`Self::play_at`], the following turns (and their subvariations) are replayed in the new positions.
+    /// If any of them becomes illegal, an error is returned and this variation is left unchanged.
+    /// The subvariations of the turn at `index` are kept, since they start at the same position.
+    ///
+    /// # Errors
+    ///
+    /// See [`ReplaceMoveError`].
+    pub fn try_replace_keeping_tail(&mut self, index: usize, r#move: Move) -> Result<(), ReplaceMoveError> {
+        if index >= self.turns.len() {
+            return Err(ReplaceMoveError::NoTurnAt { index });
+        }
+
+        let mut position = self.get_position(index).ok_or(ReplaceMoveError::NoTurnAt { index })?.clone();
+
+        if !position.is_legal(&r#move) {
+            return Err(ReplaceMoveError::PlayError(VariationPlayError {
+                turn_index: index,
+                r#move,
+            }));
+        }
+
+        position.play_unchecked(&r#move);
+
+        let tail_index = index.saturating_add(1);
+        let tail = replay_turns(position.clone(), self.turns.get(tail_index..).unwrap_or(&[]))
+            .map_err(|tail_i| ReplaceMoveError::TailBecameIllegal { turn_index: tail_index.saturating_add(tail_i) })?;
+
+        self.turns.truncate(tail_index);
+        self.turns.extend(tail);
+
+        // CLIPPY: `index` was checked to be in bounds and the turns up to `index` were not removed.
+        #[allow(clippy::unwrap_used)]
+        let turn = self.get_turn_mut(index).unwrap();
+        turn.r#move = r#move;
+        turn.position_after = position;
+
+        Ok(())
+    }
+
     // CLIPPY: This function never panics; all panicking functions are explained.
     #[allow(clippy::missing_panics_doc)]
     /// See [`Self::play_at`].
@@ -275,106 +701,641 @@ impl Variation {
         self.turns.pop()
     }
 
-    /// Inserts a variation to the turn at the specified index.
-    /// 
-    /// The new variation must have the same starting position as this variation's position at `index`.
+    /// Inserts a move before the turn at `index`, keeping the turns after it.
+    ///
+    /// See [`Self::insert_moves_at`].
     ///
     /// # Errors
     ///
-    /// See [`InsertVariationError`].
-    pub fn insert_variation(&mut self, index: usize, variation: Self) -> Result<(), InsertVariationError> {
-        let position_at_index = self.get_position(index).ok_or(InsertVariationError::NoSuchTurn { index })?.clone();
+    /// See [`InsertMoveError`].
+    pub fn insert_move_at(&mut self, index: usize, r#move: Move) -> Result<(), InsertMoveError> {
+        self.insert_moves_at(index, [r#move])
+    }
 
-        if variation.first_position != position_at_index {
-            return Err(InsertVariationError::PositionDoesNotMatch {
-                position_at_index: Box::new(position_at_index),
-                new_variation_first_position: Box::new(variation.first_position)
-            });
+    /// Inserts moves before the turn at `index`, keeping the turns after them.
+    ///
+    /// The turns after the inserted moves (and their subvariations) are replayed in the new positions.
+    /// If any of them becomes illegal, an error is returned and this variation is left unchanged.
+    /// Note that inserting an odd amount of moves changes which side plays each of the following moves,
+    /// so this will only succeed if there are no following moves.
+    ///
+    /// `index` may be equal to the amount of turns, in which case the moves are appended.
+    ///
+    /// # Errors
+    ///
+    /// See [`InsertMoveError`].
+    pub fn insert_moves_at(&mut self, index: usize, moves: impl IntoIterator<Item = Move>) -> Result<(), InsertMoveError> {
+        if index > self.turns.len() {
+            return Err(InsertMoveError::NoTurnAt { index });
         }
 
-        self.get_turn_mut(index)
-            .ok_or(InsertVariationError::NoSuchTurn { index })?
-            .variations
-            .push(variation);
+        let mut position = self.get_position(index).ok_or(InsertMoveError::NoTurnAt { index })?.clone();
+        let mut new_turns = Vec::new();
+
+        for (move_i, r#move) in moves.into_iter().enumerate() {
+            if !position.is_legal(&r#move) {
+                return Err(InsertMoveError::PlayError(VariationPlayError {
+                    turn_index: index.saturating_add(move_i),
+                    r#move,
+                }));
+            }
+
+            position.play_unchecked(&r#move);
+            new_turns.push(Turn::new(r#move, VariationsCapacity::default(), position.clone()));
+        }
+
+        let tail = replay_turns(position, self.turns.get(index..).unwrap_or(&[]))
+            .map_err(|tail_i| InsertMoveError::TailBecameIllegal { turn_index: index.saturating_add(tail_i) })?;
+
+        self.turns.truncate(index);
+        self.turns.append(&mut new_turns);
+        self.turns.extend(tail);
 
         Ok(())
     }
-}
 
-fn fmt(f: &mut Formatter<'_>, mut move_number: MoveNumber, variation: &Variation, mut very_first_move: bool) -> std::fmt::Result {
-    for turn_i in 0..variation.turns.len() {
-        // CLIPPY: The above for loop ensures the index is within bounds.
-        #[allow(clippy::unwrap_used)]
-        let Turn { position_after, r#move, variations: subvariations } = variation.turns.get(turn_i).unwrap();
+    /// Returns the amount of turns in this variation and all of its subvariations.
+    pub fn total_turn_count(&self) -> usize {
+        self.turns.iter().fold(self.turns.len(), |count, turn| {
+            turn.variations.iter().fold(count, |count, subvariation| count.saturating_add(subvariation.total_turn_count()))
+        })
+    }
 
-        if very_first_move {
-            very_first_move = false;
-        } else {
-            f.write_char(' ')?;
-        }
+    /// Replaces the first position and replays every turn (and subvariation) on it.
+    ///
+    /// If any move becomes illegal, an error is returned and this variation is left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// See [`RebaseError`].
+    pub fn rebase(&mut self, new_first_position: Chess) -> Result<(), RebaseError> {
+        self.turns = replay_turns(new_first_position.clone(), &self.turns).map_err(|turn_index| RebaseError { turn_index })?;
+        self.first_position = new_first_position;
 
-        f.write_str(&move_number.number().to_string())?;
+        Ok(())
+    }
 
-        if move_number.color().is_white() {
-            f.write_str(". ")?;
-        } else {
-            f.write_str("... ")?;
-        }
+    /// Returns the mainline as it appears in the PGN, split into move number and SAN tokens,
+    /// e.g. `["1.", "e4", "1...", "e5"]`. Numbering starts at [`Self::first_move_number`].
+    pub fn tokens(&self) -> Vec<String> {
+        let mut tokens = Vec::with_capacity(self.turns.len().saturating_mul(2));
+        let first_move_number = self.first_move_number();
 
-        // You would think that using the position *after* the move would mess up `San::from_move`,
-        // but nope. Tests pass.
-        f.write_str(&SanPlus {
-            san: San::from_move(position_after, r#move),
-            suffix: Suffix::from_position(position_after),
-        }.to_string())?;
+        for turn_i in 0..self.turns.len() {
+            tokens.push(MoveNumber(first_move_number.0.saturating_add(turn_i)).to_pgn_token());
 
-        for subvariation in subvariations {
-            f.write_str(" (")?;
-            fmt(f, move_number, subvariation, false)?;
-            f.write_str(" )")?;
+            // CLIPPY: The above for loop ensures the index is within bounds.
+            #[allow(clippy::unwrap_used)]
+            tokens.push(self.get_san_plus(turn_i).unwrap().to_string());
         }
 
-        // CLIPPY: There's never going to be u16::MAX moves.
-        #[allow(clippy::arithmetic_side_effects)]
-        {
-            move_number.0 += 1;
-        }
+        tokens
     }
 
-    Ok(())
-}
-
-impl Display for Variation {
-    /// Displays the PGN movelist representation of this variation.
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        fmt(f, MoveNumber::MIN, self, true)
+    /// Returns `true` if any turn has a subvariation.
+    pub fn has_variations(&self) -> bool {
+        self.turns.iter().any(|turn| !turn.variations.is_empty())
     }
-}
 
-/// Plays the given moves in the variation, returning the first error.
-///
-/// Syntax: `play_moves!(variation, move1, move2, ..)`.
-#[macro_export]
-macro_rules! play_moves {
-    ($variation:expr, $($r#move:expr),*) => {
-        {
-            use ::shakmaty::{PlayError, Chess};
-            use $crate::Variation;
-            fn play_moves(variation: &mut Variation) -> Result<(), PlayError<Chess>> {
-                $(
-                variation.play($r#move)?;
-                )*
-                
-                Ok(())
+    /// Returns the SANs of the mainline separated by spaces, without move numbers or subvariations,
+    /// e.g. `e4 e5 Nf3 Nc6`. Useful for search indexing.
+    pub fn mainline_moves_string(&self) -> String {
+        let mut string = String::with_capacity(self.turns.len().saturating_mul(5));
+
+        for turn_i in 0..self.turns.len() {
+            let Some(san_plus) = self.get_san_plus(turn_i) else {
+                break;
+            };
+
+            if turn_i > 0 {
+                string.push(' ');
             }
-            
-            play_moves(&mut $variation)
+
+            string.push_str(&san_plus.to_string());
         }
-    };
-}
 
-/// Plays the given SANs in the variation, returning the first error.
-///
+        string
+    }
+
+    /// Returns the mainline moves in long algebraic notation, separated by spaces,
+    /// e.g. `e2-e4 e7-e5 Ng1-f3`. Captures use `x` instead of `-` and checks are suffixed like in SAN.
+    pub fn to_long_algebraic(&self) -> String {
+        let mut string = String::with_capacity(self.turns.len().saturating_mul(7));
+
+        for (turn_i, turn) in self.turns.iter().enumerate() {
+            if turn_i > 0 {
+                string.push(' ');
+            }
+
+            push_long_algebraic(&mut string, &turn.r#move, &turn.position_after);
+        }
+
+        string
+    }
+
+    /// Returns the mainline index of the first move that differs between `self` and `other`,
+    /// or [`None`] if one mainline is a prefix of the other (including if they're identical).
+    ///
+    /// Only the moves are compared, so the first positions should be the same for the result to be meaningful.
+    pub fn first_divergence(&self, other: &Self) -> Option<usize> {
+        self.turns.iter().zip(&other.turns).position(|(turn, other_turn)| turn.r#move != other_turn.r#move)
+    }
+
+    /// Returns the mainline turn indices in `self` and `other` after which the positions first coincide
+    /// even though the moves leading to them differ, e.g. `(2, 2)` for `1. Nf3 d5 2. d4` and `1. d4 d5 2. Nf3`.
+    ///
+    /// Only positions after the mainlines diverge (see [`Self::first_divergence`]) are compared,
+    /// so a position that is repeated within a shared line isn't a transposition.
+    /// Consequently, there's none if one mainline is a prefix of the other.
+    ///
+    /// Only the first `within_plies` turns of each mainline are compared.
+    /// Positions are compared like in [`Self::max_repetition_count`], and the first positions should be the same
+    /// for the result to be meaningful.
+    pub fn transposes_to(&self, other: &Self, within_plies: usize) -> Option<(usize, usize)> {
+        let divergence = self.first_divergence(other)?;
+        let mut other_indices = HashMap::with_capacity(other.turns.len().min(within_plies).saturating_sub(divergence));
+
+        for (other_turn_i, other_turn) in other.turns.iter().enumerate().take(within_plies).skip(divergence) {
+            other_indices.entry(position_key(&other_turn.position_after)).or_insert(other_turn_i);
+        }
+
+        self.turns.iter().enumerate().take(within_plies).skip(divergence).find_map(|(turn_i, turn)| {
+            other_indices.get(&position_key(&turn.position_after)).map(|&other_turn_i| (turn_i, other_turn_i))
+        })
+    }
+
+    /// Returns the FENs of every position in this variation and its subvariations as a tree.
+    ///
+    /// The root is the first position. See [`FenNode`] for how the children are ordered.
+    /// The tree has one node more than [`Self::total_turn_count`].
+    pub fn fen_tree(&self) -> FenNode {
+        FenNode {
+            fen: Fen::from_position(self.first_position.clone(), EnPassantMode::Legal).to_string(),
+            children: self.fen_children(0),
+        }
+    }
+
+    /// The nodes that can follow the position at `index`. See [`Self::fen_tree`].
+    fn fen_children(&self, index: usize) -> Vec<FenNode> {
+        let Some(turn) = self.turns.get(index) else {
+            return Vec::new();
+        };
+
+        let mut children = Vec::with_capacity(turn.variations.len().saturating_add(1));
+
+        children.push(FenNode {
+            fen: Fen::from_position(turn.position_after.clone(), EnPassantMode::Legal).to_string(),
+            children: self.fen_children(index.saturating_add(1)),
+        });
+
+        for subvariation in &turn.variations {
+            children.extend(subvariation.fen_children(0));
+        }
+
+        children
+    }
+
+    /// Returns the maximum amount of times any position occurs in the mainline, including the first position.
+    ///
+    /// Positions are the same if the board, side to move, castling rights and legal en passant square are the same.
+    /// Move counters are ignored.
+    pub fn max_repetition_count(&self) -> usize {
+        let mut counts = HashMap::with_capacity(self.turns.len().saturating_add(1));
+
+        for position in std::iter::once(&self.first_position).chain(self.turns.iter().map(Turn::position_after)) {
+            let count: &mut usize = counts.entry(position_key(position)).or_default();
+
+            *count = count.saturating_add(1);
+        }
+
+        counts.into_values().max().unwrap_or_default()
+    }
+
+    /// Returns `true` if any position occurs at least three times in the mainline. See [`Self::max_repetition_count`].
+    pub fn has_threefold(&self) -> bool {
+        self.max_repetition_count() >= 3
+    }
+
+    /// Returns the amount of captures in the mainline (en passant included).
+    pub fn mainline_capture_count(&self) -> usize {
+        self.turns.iter().filter(|turn| turn.r#move.is_capture()).count()
+    }
+
+    /// Returns the amount of moves in the mainline that give check (checkmate included).
+    pub fn mainline_check_count(&self) -> usize {
+        self.turns.iter().filter(|turn| turn.position_after.is_check()).count()
+    }
+
+    /// Returns the mainline moves played by `color`.
+    ///
+    /// The color of the first move is determined by the side to move in [`Self::first_position`].
+    pub fn moves_by(&self, color: Color) -> impl Iterator<Item = &Move> {
+        let skip = usize::from(self.first_position.turn() != color);
+
+        self.turns.iter().skip(skip).step_by(2).map(Turn::r#move)
+    }
+
+    /// Returns the mainline moves played by white. See [`Self::moves_by`].
+    pub fn white_moves(&self) -> impl Iterator<Item = &Move> {
+        self.moves_by(Color::White)
+    }
+
+    /// Returns the mainline moves played by black. See [`Self::moves_by`].
+    pub fn black_moves(&self) -> impl Iterator<Item = &Move> {
+        self.moves_by(Color::Black)
+    }
+
+    /// Calls [`Turn::dedup_variations`] on every turn, removing duplicate subvariations in the whole tree.
+    pub fn dedup_all(&mut self) {
+        for turn in &mut self.turns {
+            turn.dedup_variations();
+        }
+    }
+
+    /// Removes all subvariations nested deeper than `max_depth`.
+    ///
+    /// A depth of 0 removes every subvariation (like [`Self::stripped_mainline`], but in place),
+    /// 1 keeps only the subvariations of this variation's turns, and so on.
+    pub fn prune_to_depth(&mut self, max_depth: usize) {
+        let Some(subvariation_max_depth) = max_depth.checked_sub(1) else {
+            for turn in &mut self.turns {
+                turn.variations.clear();
+            }
+
+            return;
+        };
+
+        for subvariation in self.turns.iter_mut().flat_map(|turn| &mut turn.variations) {
+            subvariation.prune_to_depth(subvariation_max_depth);
+        }
+    }
+
+    /// Returns a clone of this variation with all subvariations removed.
+    pub fn stripped_mainline(&self) -> Self {
+        Self {
+            first_position: self.first_position.clone(),
+            turns: self.turns.iter().map(|turn| Turn {
+                r#move: turn.r#move.clone(),
+                variations: Vec::new(),
+                position_after: turn.position_after.clone(),
+            }).collect(),
+        }
+    }
+
+    /// Returns the SANs of the longest line from the first position to the end of any (sub)variation.
+    ///
+    /// A subvariation at turn `i` replaces that turn, so the line through it is
+    /// the first `i` turns of the parent followed by the line of the subvariation.
+    /// If multiple lines are equally long, the mainline (and then the earliest subvariation) is preferred.
+    pub fn longest_line(&self) -> Vec<SanPlus> {
+        let mut longest: Option<(usize, Vec<SanPlus>)> = None;
+        let mut longest_len = self.turns.len();
+
+        for (turn_i, turn) in self.turns.iter().enumerate() {
+            for subvariation in &turn.variations {
+                let line = subvariation.longest_line();
+                let len = turn_i.saturating_add(line.len());
+
+                if len > longest_len {
+                    longest_len = len;
+                    longest = Some((turn_i, line));
+                }
+            }
+        }
+
+        let (parent_len, tail) = longest.unwrap_or((self.turns.len(), Vec::new()));
+        let mut line = Vec::with_capacity(longest_len);
+
+        line.extend((0..parent_len).filter_map(|turn_i| self.get_san_plus(turn_i)));
+        line.extend(tail);
+
+        line
+    }
+
+    /// Keeps only the first `index` turns, returning the removed turns in order.
+    ///
+    /// If `index` is greater than or equal to the amount of turns, nothing is removed.
+    /// The removed turns can be used to implement a redo stack.
+    pub fn undo_to(&mut self, index: usize) -> Vec<Turn> {
+        if index >= self.turns.len() {
+            return Vec::new();
+        }
+
+        self.turns.split_off(index)
+    }
+
+    /// Recursively shrinks the capacity of all turns and variations as much as possible.
+    ///
+    /// Useful before storing many variations in memory.
+    /// See [`Vec::shrink_to_fit`].
+    pub fn shrink_to_fit(&mut self) {
+        self.turns.shrink_to_fit();
+
+        for turn in &mut self.turns {
+            turn.variations.shrink_to_fit();
+
+            for subvariation in &mut turn.variations {
+                subvariation.shrink_to_fit();
+            }
+        }
+    }
+
+    /// Recursively sums the lengths and capacities of all turns and variations.
+    ///
+    /// Useful for tuning [`TurnsCapacity`] and [`VariationsCapacity`].
+    pub fn capacity_stats(&self) -> CapacityStats {
+        let mut stats = CapacityStats {
+            turns_len: self.turns.len(),
+            turns_capacity: self.turns.capacity(),
+            ..CapacityStats::default()
+        };
+
+        for turn in &self.turns {
+            stats.variations_len = stats.variations_len.saturating_add(turn.variations.len());
+            stats.variations_capacity = stats.variations_capacity.saturating_add(turn.variations.capacity());
+
+            for subvariation in &turn.variations {
+                let subvariation_stats = subvariation.capacity_stats();
+
+                stats.turns_len = stats.turns_len.saturating_add(subvariation_stats.turns_len);
+                stats.turns_capacity = stats.turns_capacity.saturating_add(subvariation_stats.turns_capacity);
+                stats.variations_len = stats.variations_len.saturating_add(subvariation_stats.variations_len);
+                stats.variations_capacity = stats.variations_capacity.saturating_add(subvariation_stats.variations_capacity);
+            }
+        }
+
+        stats
+    }
+
+    /// Inserts a variation to the turn at the specified index.
+    /// 
+    /// The new variation must have the same starting position as this variation's position at `index`.
+    ///
+    /// # Errors
+    ///
+    /// See [`InsertVariationError`].
+    pub fn insert_variation(&mut self, index: usize, variation: Self) -> Result<(), InsertVariationError> {
+        let position_at_index = self.get_position(index).ok_or(InsertVariationError::NoSuchTurn { index })?;
+
+        if variation.first_position != *position_at_index {
+            return Err(InsertVariationError::PositionDoesNotMatch {
+                position_at_index: Box::new(position_at_index.clone()),
+                new_variation_first_position: Box::new(variation.first_position)
+            });
+        }
+
+        self.get_turn_mut(index)
+            .ok_or(InsertVariationError::NoSuchTurn { index })?
+            .variations
+            .push(variation);
+
+        Ok(())
+    }
+
+    /// Same as [`Self::insert_variation`], but does nothing if the turn already has an identical variation.
+    ///
+    /// This makes it safe to apply the same analysis repeatedly.
+    ///
+    /// # Errors
+    ///
+    /// See [`InsertVariationError`].
+    pub fn upsert_variation(&mut self, index: usize, variation: Self) -> Result<UpsertResult, InsertVariationError> {
+        if self.turns.get(index).is_some_and(|turn| turn.variations.contains(&variation)) {
+            return Ok(UpsertResult::AlreadyPresent);
+        }
+
+        self.insert_variation(index, variation).map(|()| UpsertResult::Added)
+    }
+}
+
+/// Pushes the long algebraic notation of `r#move` to `string`. See [`Variation::to_long_algebraic`].
+fn push_long_algebraic(string: &mut String, r#move: &Move, position_after: &Chess) {
+    if let Some(side) = r#move.castling_side() {
+        string.push_str(if side.is_king_side() { "O-O" } else { "O-O-O" });
+    } else {
+        if r#move.role() != Role::Pawn {
+            string.push(r#move.role().upper_char());
+        }
+
+        if let Some(from) = r#move.from() {
+            string.push_str(&from.to_string());
+            string.push(if r#move.is_capture() { 'x' } else { '-' });
+        }
+
+        string.push_str(&r#move.to().to_string());
+
+        if let Some(promotion) = r#move.promotion() {
+            string.push('=');
+            string.push(promotion.upper_char());
+        }
+    }
+
+    if let Some(suffix) = Suffix::from_position(position_after) {
+        string.push(suffix.char());
+    }
+}
+
+/// Plays `turns` starting from `position`, recomputing the positions of every turn and subvariation.
+///
+/// Returns the index of the first turn in `turns` that is illegal (or whose subvariations are illegal).
+fn replay_turns(mut position: Chess, turns: &[Turn]) -> Result<Vec<Turn>, usize> {
+    let mut new_turns = Vec::with_capacity(turns.len());
+
+    for (turn_i, turn) in turns.iter().enumerate() {
+        if !position.is_legal(&turn.r#move) {
+            return Err(turn_i);
+        }
+
+        let mut variations = Vec::with_capacity(turn.variations.len());
+
+        for subvariation in &turn.variations {
+            variations.push(Variation {
+                first_position: position.clone(),
+                turns: replay_turns(position.clone(), &subvariation.turns).map_err(|_| turn_i)?,
+            });
+        }
+
+        position.play_unchecked(&turn.r#move);
+        new_turns.push(Turn {
+            r#move: turn.r#move.clone(),
+            variations,
+            position_after: position.clone(),
+        });
+    }
+
+    Ok(new_turns)
+}
+
+/// Returns the SAN of `move` played in `position_before`, with the check/checkmate suffix
+/// derived from the position after the move is played.
+///
+/// `move` is assumed to be legal in `position_before`.
+pub fn san_plus_from_move(position_before: &Chess, r#move: &Move) -> SanPlus {
+    let mut position_after = position_before.clone();
+    position_after.play_unchecked(r#move);
+
+    SanPlus {
+        san: San::from_move(position_before, r#move),
+        suffix: Suffix::from_position(&position_after),
+    }
+}
+
+/// What makes positions the same for repetitions and transpositions: the board, side to move,
+/// castling rights and legal en passant square. Move counters are ignored.
+fn position_key(position: &Chess) -> (Board, Color, Bitboard, Option<Square>) {
+    (position.board().clone(), position.turn(), position.castles().castling_rights(), position.ep_square(EnPassantMode::Legal))
+}
+
+/// Returns the figurine of a SAN piece letter, e.g. `♘` for `N`.
+const fn figurine(piece: char) -> Option<char> {
+    match piece {
+        'K' => Some('♔'),
+        'Q' => Some('♕'),
+        'R' => Some('♖'),
+        'B' => Some('♗'),
+        'N' => Some('♘'),
+        _ => None,
+    }
+}
+
+fn fmt(f: &mut Formatter<'_>, mut move_number: MoveNumber, variation: &Variation, mut very_first_move: bool, config: PgnWriteConfig) -> std::fmt::Result {
+    let mut previous_turn_had_variations = false;
+
+    for turn_i in 0..variation.turns.len() {
+        // CLIPPY: The above for loop ensures the index is within bounds.
+        #[allow(clippy::unwrap_used)]
+        let Turn { variations: subvariations, .. } = variation.turns.get(turn_i).unwrap();
+
+        if very_first_move {
+            very_first_move = false;
+        } else {
+            f.write_char(' ')?;
+        }
+
+        // The first move of a variation is black's if it starts from a black to move position (e.g. a subvariation or a FEN),
+        // and it needs the `N...` number just like a black move following a subvariation does.
+        if move_number.color().is_white() || config.explicit_black_numbers || turn_i == 0 || previous_turn_had_variations {
+            move_number.write_padded_pgn_token(f, config.number_width.unwrap_or_default())?;
+            f.write_char(' ')?;
+        }
+
+        // CLIPPY: The above for loop ensures the index is within bounds.
+        #[allow(clippy::unwrap_used)]
+        let san_plus = variation.get_san_plus(turn_i).unwrap().to_string();
+
+        if config.figurine {
+            let mut chars = san_plus.chars();
+
+            match chars.next().and_then(figurine) {
+                Some(figurine) => {
+                    f.write_char(figurine)?;
+                    f.write_str(chars.as_str())?;
+                },
+                None => f.write_str(&san_plus)?,
+            }
+        } else {
+            f.write_str(&san_plus)?;
+        }
+
+        for subvariation in subvariations {
+            f.write_str(" (")?;
+            fmt(f, move_number, subvariation, false, config)?;
+            f.write_str(" )")?;
+        }
+
+        previous_turn_had_variations = !subvariations.is_empty();
+
+        // CLIPPY: There's never going to be u16::MAX moves.
+        #[allow(clippy::arithmetic_side_effects)]
+        {
+            move_number.0 += 1;
+        }
+    }
+
+    Ok(())
+}
+
+/// Displays a [`Variation`] with a [`PgnWriteConfig`].
+///
+/// See [`Variation::display_with`].
+#[derive(Debug, Clone, Copy)]
+pub struct VariationDisplay<'a> {
+    variation: &'a Variation,
+    config: PgnWriteConfig,
+}
+
+impl Display for VariationDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fmt(f, self.variation.first_move_number(), self.variation, true, self.config)
+    }
+}
+
+impl Variation {
+    /// Returns a value that displays the PGN movelist representation of this variation according to `config`.
+    pub const fn display_with(&self, config: PgnWriteConfig) -> VariationDisplay<'_> {
+        VariationDisplay {
+            variation: self,
+            config,
+        }
+    }
+
+    /// Returns the PGN movelist representation of this variation followed by the result token, e.g. `1. e4 1... e5 1-0`.
+    ///
+    /// This is how the movetext appears in a complete PGN, as opposed to [`Display`], which has no result.
+    pub fn to_pgn(&self, outcome: Outcome) -> String {
+        let outcome = outcome.to_string();
+
+        if self.turns.is_empty() {
+            return outcome;
+        }
+
+        let movetext = self.to_string();
+
+        crate::concat_strings!(&movetext, " ", &outcome)
+    }
+}
+
+impl Display for Variation {
+    /// Displays the PGN movelist representation of this variation.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.display_with(PgnWriteConfig::default()), f)
+    }
+}
+
+impl<'a> IntoIterator for &'a Variation {
+    type Item = &'a Turn;
+    type IntoIter = std::slice::Iter<'a, Turn>;
+
+    /// Iterates over the mainline turns of this variation.
+    fn into_iter(self) -> Self::IntoIter {
+        self.turns.iter()
+    }
+}
+
+/// Plays the given moves in the variation, returning the first error.
+///
+/// Syntax: `play_moves!(variation, move1, move2, ..)`.
+#[macro_export]
+macro_rules! play_moves {
+    ($variation:expr, $($r#move:expr),*) => {
+        {
+            use ::shakmaty::{PlayError, Chess};
+            use $crate::Variation;
+            fn play_moves(variation: &mut Variation) -> Result<(), PlayError<Chess>> {
+                $(
+                variation.play($r#move)?;
+                )*
+                
+                Ok(())
+            }
+            
+            play_moves(&mut $variation)
+        }
+    };
+}
+
+/// Plays the given SANs in the variation, returning the first error.
+///
 /// Syntax: `play_sans!(variation, san1, san2, ..)`.
 #[macro_export]
 macro_rules! play_sans {
@@ -433,7 +1394,7 @@ pub(crate) use play_sans;
 // This is used in tests.
 #[allow(unused_imports)]
 pub(crate) use play_san_strings;
-use crate::MoveNumber;
+use crate::{MoveNumber, PgnWriteConfig, pgn::{Outcome, Pgn, PgnParseError}};
 
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
@@ -441,9 +1402,22 @@ use crate::MoveNumber;
 mod tests {
     use super::*;
     use crate::samples::*;
+    use std::str::FromStr;
     use test_case::test_case;
     use pretty_assertions::assert_eq;
     
+    #[test_case("1. g4 e5 2. f3 Qh4# 0-1", &variation_sample1(); "without variations")]
+    #[test_case(&variation_sample2().to_string(), &variation_sample2(); "with variations")]
+    #[test_case("", &Variation::new(Chess::default(), TurnsCapacity::default()); "empty")]
+    fn from_movetext_str(movetext: &str, variation: &Variation) {
+        assert_eq!(&Variation::from_movetext_str(movetext).unwrap(), variation);
+    }
+
+    #[test]
+    fn from_illegal_movetext_str() {
+        assert!(matches!(Variation::from_movetext_str("1. e4 e4"), Err(PgnParseError::SanError(_))));
+    }
+
     #[test_case(&variation_sample0())]
     #[test_case(&variation_sample1())]
     #[test_case(&variation_sample2())]
@@ -533,4 +1507,749 @@ mod tests {
         position_before_last_move(&var);
         position_after_last_move(&var);
     }
+
+    #[test_case(variation_sample0())]
+    #[test_case(variation_sample1())]
+    #[test_case(variation_sample2())]
+    #[test_case(variation_sample6())]
+    fn shrink_to_fit(mut var: Variation) {
+        let original = var.clone();
+
+        var.shrink_to_fit();
+
+        let stats = var.capacity_stats();
+
+        assert_eq!(stats.turns_capacity, stats.turns_len);
+        assert_eq!(stats.variations_capacity, stats.variations_len);
+        assert_eq!(var, original);
+    }
+
+    #[test]
+    fn get_variation_by_path() {
+        let var = variation_sample2();
+
+        assert_eq!(var.get_variation_by_path(&[]), Some(&var));
+        assert_eq!(var.get_variation_by_path(&[0, 0]).unwrap().get_san_plus(0).unwrap().to_string(), "d4");
+        assert_eq!(var.get_variation_by_path(&[0, 0, 1, 0]).unwrap().get_san_plus(0).unwrap().to_string(), "f5");
+        assert_eq!(var.get_variation_by_path(&[0, 0, 1, 0, 1, 0]).unwrap().get_san_plus(0).unwrap().to_string(), "c4");
+        assert_eq!(var.get_variation_by_path(&[5, 0]).unwrap().get_san_plus(0).unwrap().to_string(), "Bc5");
+        assert_eq!(var.get_variation_by_path(&[0]), None);
+        assert_eq!(var.get_variation_by_path(&[1, 0]), None);
+        assert_eq!(var.get_variation_by_path(&[0, 1]), None);
+    }
+
+    #[test]
+    fn sibling_paths() {
+        let mut var = variation_sample2();
+        let mut nge7_var = Variation::new(var.get_position(5).unwrap().clone(), TurnsCapacity(1));
+
+        play_san_strings!(nge7_var, "Nge7").unwrap();
+        var.insert_variation(5, nge7_var).unwrap();
+
+        assert_eq!(var.turns().get(5).unwrap().variation_count(), 2);
+        assert_eq!(var.next_sibling_path(&[5, 0]), Some(vec![5, 1]));
+        assert_eq!(var.next_sibling_path(&[5, 1]), None);
+        assert_eq!(var.prev_sibling_path(&[5, 1]), Some(vec![5, 0]));
+        assert_eq!(var.prev_sibling_path(&[5, 0]), None);
+        assert_eq!(var.next_sibling_path(&[]), None);
+        assert_eq!(var.next_sibling_path(&[0, 0, 1, 0]), None);
+    }
+
+    #[test_case(variation_sample0(), true)]
+    #[test_case(variation_sample1(), false)]
+    #[test_case(variation_sample2(), true)]
+    #[test_case(variation_sample6(), false)]
+    fn stripped_mainline(var: Variation, has_variations: bool) {
+        let stripped = var.stripped_mainline();
+
+        assert_eq!(var.has_variations(), has_variations);
+        assert!(!stripped.has_variations());
+        assert!(!stripped.to_string().contains(['(', ')']));
+        assert_eq!(stripped.turns().len(), var.turns().len());
+        assert_eq!(stripped.position_after_last_move(), var.position_after_last_move());
+    }
+
+    #[test]
+    fn insert_moves_at() {
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "e4", "e5", "Bc4", "Nf6").unwrap();
+
+        let mut correct = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(correct, "e4", "e5", "Nf3", "Nc6", "Bc4", "Nf6").unwrap();
+
+        let nf3 = correct.turns().get(2).unwrap().r#move().clone();
+        let nc6 = correct.turns().get(3).unwrap().r#move().clone();
+
+        var.insert_moves_at(2, [nf3, nc6]).unwrap();
+
+        assert_eq!(var, correct);
+    }
+
+    #[test]
+    fn insert_move_at() {
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "e4", "e5", "Bc4").unwrap();
+
+        let original = var.clone();
+        let nf3 = San::from_str("Nf3").unwrap().to_move(var.get_position(2).unwrap()).unwrap();
+
+        assert_eq!(var.insert_move_at(2, nf3.clone()), Err(InsertMoveError::TailBecameIllegal { turn_index: 2 }));
+        assert_eq!(var, original);
+
+        var.pop();
+        var.insert_move_at(2, nf3.clone()).unwrap();
+
+        assert_eq!(var.turns().len(), 3);
+        assert_eq!(var.turns().last().unwrap().r#move(), &nf3);
+        assert_eq!(var.insert_move_at(5, nf3), Err(InsertMoveError::NoTurnAt { index: 5 }));
+
+        let e4 = original.turns().first().unwrap().r#move().clone();
+
+        assert!(matches!(var.insert_move_at(0, e4.clone()), Err(InsertMoveError::TailBecameIllegal { turn_index: 0 })));
+        assert!(matches!(var.insert_move_at(1, e4), Err(InsertMoveError::PlayError(_))));
+    }
+
+    #[test]
+    fn promote_path() {
+        let mut var = variation_sample2();
+
+        var.promote_path(&[0, 0, 1, 0]).unwrap();
+
+        assert_eq!(var.to_string(), "1. e4 ( 1. d4 1... f5 ( 1... d5 ) 2. g3 ( 2. c4 2... Nf6 3. Nc3 3... e6 ( 3... g6 ) 4. Nf3 ) 2... Nf6 ) 1... e5 2. Nf3 2... Nc6 3. Bc4 3... Nf6 ( 3... Bc5 ) 4. d3");
+
+        var.promote_path(&[0, 0, 1, 0]).unwrap();
+
+        assert_eq!(var, variation_sample2());
+
+        var.promote_path(&[5, 0]).unwrap();
+
+        assert_eq!(var.to_string(), "1. e4 ( 1. d4 1... d5 ( 1... f5 2. g3 ( 2. c4 2... Nf6 3. Nc3 3... e6 ( 3... g6 ) 4. Nf3 ) 2... Nf6 ) ) 1... e5 2. Nf3 2... Nc6 3. Bc4 3... Bc5 ( 3... Nf6 4. d3 )");
+
+        get_position(&var);
+        position_after_last_move(&var);
+        position_before_last_move(&var);
+
+        assert_eq!(var.promote_path(&[]), Err(PromoteError::NoSuchVariation));
+        assert_eq!(var.promote_path(&[5]), Err(PromoteError::NoSuchVariation));
+        assert_eq!(var.promote_path(&[5, 1]), Err(PromoteError::NoSuchVariation));
+        assert_eq!(var.promote_path(&[1, 0]), Err(PromoteError::NoSuchVariation));
+    }
+
+    #[test]
+    fn promote_empty_path() {
+        let mut var = variation_sample1();
+
+        var.insert_variation(1, Variation::new(var.get_position(1).unwrap().clone(), TurnsCapacity(0))).unwrap();
+
+        assert_eq!(var.promote_path(&[1, 0]), Err(PromoteError::EmptyVariation));
+    }
+
+    #[test]
+    fn display_with_implicit_black_numbers() {
+        let config = PgnWriteConfig {
+            explicit_black_numbers: false,
+            ..PgnWriteConfig::default()
+        };
+
+        assert_eq!(variation_sample1().display_with(config).to_string(), "1. g4 e5 2. f3 Qh4#");
+        assert_eq!(variation_sample0().display_with(config).to_string(), "1. e4 ( 1. d4 d5 ( 1... f5 ) ) 1... e5 2. Nf3 Nc6 3. Bc4 Nf6 ( 3... Bc5 ) 4. Nc3");
+        assert_eq!(variation_sample0().display_with(PgnWriteConfig::default()).to_string(), variation_sample0().to_string());
+    }
+
+    #[test]
+    fn display_with_implicit_black_numbers_black_first() {
+        let config = PgnWriteConfig {
+            explicit_black_numbers: false,
+            ..PgnWriteConfig::default()
+        };
+        let position = Fen::from_ascii(b"4k3/8/8/8/8/8/4P3/4K3 b - - 0 20").unwrap().into_position(shakmaty::CastlingMode::Standard).unwrap();
+        let mut var = Variation::new(position, TurnsCapacity::default());
+
+        play_san_strings!(var, "Kd7", "e4", "Ke6").unwrap();
+
+        assert_eq!(var.display_with(config).to_string(), "20... Kd7 21. e4 Ke6");
+        assert_eq!(var.to_string(), "20... Kd7 21. e4 21... Ke6");
+    }
+
+    #[test]
+    fn display_with_number_width() {
+        let config = PgnWriteConfig {
+            explicit_black_numbers: false,
+            number_width: Some(2),
+            ..PgnWriteConfig::default()
+        };
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1").unwrap();
+
+        assert_eq!(
+            var.display_with(config).to_string(),
+            " 1. Nf3 Nf6  2. Ng1 Ng8  3. Nf3 Nf6  4. Ng1 Ng8  5. Nf3 Nf6  6. Ng1"
+        );
+
+        play_san_strings!(var, "Ng8", "Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1", "Ng8").unwrap();
+
+        assert!(var.display_with(config).to_string().ends_with(" 9. Nf3 Nf6 10. Ng1 Ng8"));
+        assert_eq!(var.display_with(PgnWriteConfig { number_width: None, ..config }).to_string(), var.display_with(PgnWriteConfig { number_width: Some(1), ..config }).to_string());
+    }
+
+    #[test]
+    fn display_with_figurine() {
+        let config = PgnWriteConfig {
+            figurine: true,
+            ..PgnWriteConfig::default()
+        };
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "Nf3", "e5", "Nxe5", "Qe7", "Nf3", "Qxe2+", "Bxe2").unwrap();
+
+        assert_eq!(var.display_with(config).to_string(), "1. ♘f3 1... e5 2. ♘xe5 2... ♕e7 3. ♘f3 3... ♕xe2+ 4. ♗xe2");
+        assert_eq!(var.to_string(), "1. Nf3 1... e5 2. Nxe5 2... Qe7 3. Nf3 3... Qxe2+ 4. Bxe2");
+        assert_eq!(
+            variation_sample0().display_with(config).to_string(),
+            "1. e4 ( 1. d4 1... d5 ( 1... f5 ) ) 1... e5 2. ♘f3 2... ♘c6 3. ♗c4 3... ♘f6 ( 3... ♗c5 ) 4. ♘c3"
+        );
+    }
+
+    #[test]
+    fn try_replace_keeping_tail() {
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "e4", "e5", "Nf3", "Nc6").unwrap();
+
+        let bc4 = San::from_str("Bc4").unwrap().to_move(var.get_position(2).unwrap()).unwrap();
+
+        var.try_replace_keeping_tail(2, bc4).unwrap();
+
+        let mut correct = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(correct, "e4", "e5", "Bc4", "Nc6").unwrap();
+
+        assert_eq!(var, correct);
+
+        let d4 = San::from_str("d4").unwrap().to_move(var.first_position()).unwrap();
+
+        assert_eq!(var.try_replace_keeping_tail(0, d4.clone()), Err(ReplaceMoveError::TailBecameIllegal { turn_index: 2 }));
+        assert_eq!(var, correct);
+        assert!(matches!(var.try_replace_keeping_tail(1, d4.clone()), Err(ReplaceMoveError::PlayError(_))));
+        assert_eq!(var.try_replace_keeping_tail(4, d4), Err(ReplaceMoveError::NoTurnAt { index: 4 }));
+    }
+
+    #[test]
+    fn rebase() {
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "Nf3", "Nf6").unwrap();
+
+        let mut nc3_var = Variation::new(var.first_position().clone(), TurnsCapacity(1));
+
+        play_san_strings!(nc3_var, "Nc3").unwrap();
+        var.insert_variation(0, nc3_var).unwrap();
+
+        let mut open_game = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(open_game, "e4", "e5").unwrap();
+
+        let original = var.clone();
+
+        assert_eq!(var.rebase(open_game.get_position(1).unwrap().clone()), Err(RebaseError { turn_index: 0 }));
+        assert_eq!(var, original);
+
+        var.rebase(open_game.position_after_last_move().clone()).unwrap();
+
+        play_san_strings!(open_game, "Nf3", "Nf6").unwrap();
+
+        assert_eq!(var.first_position(), open_game.get_position(2).unwrap());
+        assert_eq!(var.position_after_last_move(), open_game.position_after_last_move());
+        assert_eq!(var.turns().first().unwrap().variations().first().unwrap().first_position(), var.first_position());
+        assert_eq!(var.to_string(), "1. Nf3 ( 1. Nc3 ) 1... Nf6");
+    }
+
+    #[test]
+    fn tokens() {
+        assert_eq!(variation_sample1().tokens(), ["1.", "g4", "1...", "e5", "2.", "f3", "2...", "Qh4#"]);
+        assert_eq!(variation_sample0().tokens().len(), 14);
+        assert!(Variation::new(Chess::new(), TurnsCapacity(0)).tokens().is_empty());
+
+        let position = Fen::from_ascii(b"4k3/8/8/8/8/8/4P3/4K3 b - - 0 20").unwrap().into_position(shakmaty::CastlingMode::Standard).unwrap();
+        let mut var = Variation::new(position, TurnsCapacity::default());
+
+        play_san_strings!(var, "Kd7", "e4").unwrap();
+
+        assert_eq!(var.tokens(), ["20...", "Kd7", "21.", "e4"]);
+    }
+
+    #[test]
+    fn try_extend_sans() {
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+        let sans = ["e4", "e4", "e5", "Nf3", "Ke3", "Nc6"].map(|san| San::from_str(san).unwrap());
+
+        let errors = var.try_extend_sans(sans);
+
+        assert_eq!(errors, vec![(1, SanError::IllegalSan), (4, SanError::IllegalSan)]);
+        assert_eq!(var.turns().len(), 4);
+    }
+
+    #[test]
+    fn undo_to() {
+        let mut var = variation_sample0();
+        let original = var.clone();
+        let removed = var.undo_to(3);
+
+        assert_eq!(var.turns().len(), 3);
+        assert_eq!(removed.len(), 4);
+        assert_eq!(removed.first().unwrap().r#move(), original.turns().get(3).unwrap().r#move());
+        assert_eq!(var.position_after_last_move(), original.get_position(3).unwrap());
+        assert!(var.undo_to(3).is_empty());
+        assert!(var.undo_to(10).is_empty());
+        assert_eq!(var.undo_to(0).len(), 3);
+        assert!(var.turns().is_empty());
+    }
+
+    #[test_case(&variation_sample0(), 11)]
+    #[test_case(&variation_sample1(), 4)]
+    #[test_case(&variation_sample2(), 7 + 2 + 3 + 5 + 1 + 1)]
+    #[test_case(&variation_sample6(), 1)]
+    fn total_turn_count(var: &Variation, count: usize) {
+        assert_eq!(var.total_turn_count(), count);
+    }
+
+    #[test_case(&variation_sample0(), 7)]
+    #[test_case(&variation_sample1(), 4)]
+    #[test_case(&variation_sample6(), 1)]
+    fn into_iter(var: &Variation, count: usize) {
+        let mut iterated = 0_usize;
+
+        for turn in var {
+            assert_eq!(var.turns().get(iterated), Some(turn));
+            iterated = iterated.saturating_add(1);
+        }
+
+        assert_eq!(iterated, count);
+    }
+
+    #[test]
+    fn longest_line() {
+        let to_strings = |line: Vec<SanPlus>| line.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let var = variation_sample2();
+
+        // The line through `1. d4` is as long as the mainline, so the mainline is preferred.
+        assert_eq!(to_strings(var.longest_line()), ["e4", "e5", "Nf3", "Nc6", "Bc4", "Nf6", "d3"]);
+        assert_eq!(to_strings(var.get_variation_by_path(&[0, 0]).unwrap().longest_line()), ["d4", "f5", "c4", "Nf6", "Nc3", "e6", "Nf3"]);
+
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "e4", "e5").unwrap();
+
+        let mut c5_var = Variation::new(var.get_position(1).unwrap().clone(), TurnsCapacity::default());
+
+        play_san_strings!(c5_var, "c5", "Nf3", "d6").unwrap();
+        var.insert_variation(1, c5_var).unwrap();
+
+        assert_eq!(to_strings(var.longest_line()), ["e4", "c5", "Nf3", "d6"]);
+    }
+
+    #[test]
+    fn play_uci() {
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        for uci in ["e2e4", "e7e5", "g1f3"] {
+            var.play_uci(&Uci::from_ascii(uci.as_bytes()).unwrap(), VariationsCapacity::default()).unwrap();
+        }
+
+        assert_eq!(var.to_string(), "1. e4 1... e5 2. Nf3");
+
+        let illegal = Uci::from_ascii(b"e2e4").unwrap();
+
+        assert_eq!(var.play_uci(&illegal, VariationsCapacity::default()), Err(VariationUciPlayError {
+            turn_index: 3,
+            uci: illegal,
+            error: IllegalUciError,
+        }));
+    }
+
+    #[test_case(&variation_sample0(), 4, 3)]
+    #[test_case(&variation_sample1(), 2, 2)]
+    #[test_case(&variation_sample6(), 1, 0)]
+    fn white_and_black_moves(var: &Variation, white_count: usize, black_count: usize) {
+        assert_eq!(var.white_moves().count(), white_count);
+        assert_eq!(var.black_moves().count(), black_count);
+        assert!(var.white_moves().all(|r#move| var.turns().iter().step_by(2).any(|turn| turn.r#move() == r#move)));
+    }
+
+    #[test_case(&variation_sample0())]
+    #[test_case(&variation_sample1())]
+    #[test_case(&variation_sample2())]
+    #[test_case(&variation_sample6())]
+    fn fen_tree(var: &Variation) {
+        fn count(node: &FenNode) -> usize {
+            node.children.iter().map(count).fold(1, usize::saturating_add)
+        }
+
+        let tree = var.fen_tree();
+
+        assert_eq!(count(&tree), var.total_turn_count().saturating_add(1));
+        assert_eq!(tree.fen, Fen::from_position(var.first_position().clone(), EnPassantMode::Legal).to_string());
+    }
+
+    #[test]
+    fn fen_tree_order() {
+        let tree = variation_sample0().fen_tree();
+        let fens = tree.children.iter().map(|node| node.fen.as_str()).collect::<Vec<_>>();
+
+        // `1. e4` first, then the alternative `1. d4`.
+        assert_eq!(fens, [
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/3P4/8/PPP1PPPP/RNBQKBNR b KQkq - 0 1",
+        ]);
+    }
+
+    #[test]
+    fn first_divergence() {
+        // Sample 0 and sample 2 differ at the last mainline move, `4. Nc3` vs `4. d3`.
+        assert_eq!(variation_sample0().first_divergence(&variation_sample2()), Some(6));
+        assert_eq!(variation_sample0().first_divergence(&variation_sample0()), None);
+        assert_eq!(variation_sample0().first_divergence(&variation_sample6()), None);
+        assert_eq!(variation_sample6().first_divergence(&variation_sample0()), None);
+        assert_eq!(variation_sample1().first_divergence(&variation_sample0()), Some(0));
+    }
+
+    #[test]
+    fn transposes_to() {
+        let mut nf3_first = Variation::new(Chess::new(), TurnsCapacity::default());
+        let mut d4_first = Variation::new(Chess::new(), TurnsCapacity::default());
+        let mut long_way = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(nf3_first, "Nf3", "d5", "d4").unwrap();
+        play_san_strings!(d4_first, "d4", "d5", "Nf3").unwrap();
+        play_san_strings!(long_way, "Nf3", "Nf6", "Ng1", "Ng8", "d4").unwrap();
+
+        assert_eq!(nf3_first.transposes_to(&d4_first, 3), Some((2, 2)));
+        assert_eq!(d4_first.transposes_to(&nf3_first, 10), Some((2, 2)));
+        assert_eq!(nf3_first.transposes_to(&d4_first, 2), None);
+        assert_eq!(nf3_first.transposes_to(&nf3_first, 10), None);
+        assert_eq!(long_way.transposes_to(&d4_first, 10), Some((4, 0)));
+        assert_eq!(variation_sample0().transposes_to(&variation_sample2(), 10), None);
+    }
+
+    #[test]
+    fn transposes_to_repeated_position() {
+        let mut shuffle = Variation::new(Chess::new(), TurnsCapacity::default());
+        let mut repeated = Variation::new(Chess::new(), TurnsCapacity::default());
+        let mut nf3_first = Variation::new(Chess::new(), TurnsCapacity::default());
+        let mut d4_first = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(shuffle, "Nf3", "Nf6", "Ng1", "Ng8", "Nf3").unwrap();
+        play_san_strings!(repeated, "Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "d5", "d4").unwrap();
+        play_san_strings!(nf3_first, "Nf3", "d5", "d4").unwrap();
+        play_san_strings!(d4_first, "d4", "d5", "Nf3").unwrap();
+
+        // The position after `1. Nf3` is repeated, but it's reached before the lines diverge.
+        assert_eq!(nf3_first.transposes_to(&shuffle, 10), None);
+        assert_eq!(shuffle.transposes_to(&nf3_first, 10), None);
+        assert_eq!(repeated.transposes_to(&repeated, 10), None);
+        // `1. Nf3 d5` is reached after the lines diverge.
+        assert_eq!(nf3_first.transposes_to(&repeated, 10), Some((1, 5)));
+        assert_eq!(repeated.transposes_to(&nf3_first, 10), Some((5, 1)));
+        assert_eq!(repeated.transposes_to(&nf3_first, 5), None);
+        assert_eq!(repeated.transposes_to(&d4_first, 10), Some((6, 2)));
+    }
+
+    #[test]
+    fn max_repetition_count() {
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "Nf3", "Nf6", "Ng1", "Ng8").unwrap();
+
+        assert_eq!(var.max_repetition_count(), 2);
+        assert!(!var.has_threefold());
+
+        play_san_strings!(var, "Nf3", "Nf6", "Ng1", "Ng8").unwrap();
+
+        assert_eq!(var.max_repetition_count(), 3);
+        assert!(var.has_threefold());
+        assert_eq!(variation_sample1().max_repetition_count(), 1);
+        assert_eq!(Variation::new(Chess::new(), TurnsCapacity::default()).max_repetition_count(), 1);
+    }
+
+    #[test]
+    fn turn_move_classification() {
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(
+            var,
+            "e4", "d5", "e5", "f5", "exf6", "Nc6", "fxg7", "Be6", "gxh8=Q", "Qd7", "Nf3", "O-O-O", "Be2", "a6", "O-O"
+        ).unwrap();
+
+        let turn = |index: usize| var.turns().get(index).unwrap();
+
+        assert!(turn(4).is_en_passant());
+        assert_eq!(turn(8).promotion(), Some(Role::Queen));
+        assert_eq!(turn(11).castling_side(), Some(CastlingSide::QueenSide));
+        assert_eq!(turn(14).castling_side(), Some(CastlingSide::KingSide));
+        assert!(!turn(0).is_en_passant());
+        assert_eq!(turn(0).promotion(), None);
+        assert_eq!(turn(0).castling_side(), None);
+    }
+
+    #[test_case(&variation_sample0(), "e4 e5 Nf3 Nc6 Bc4 Nf6 Nc3")]
+    #[test_case(&variation_sample1(), "g4 e5 f3 Qh4#")]
+    #[test_case(&variation_sample6(), "e4")]
+    #[test_case(&Variation::new(Chess::new(), TurnsCapacity(0)), "")]
+    fn mainline_moves_string(var: &Variation, string: &str) {
+        assert_eq!(var.mainline_moves_string(), string);
+    }
+
+    #[test_case(Outcome::Decisive { winner: Color::White }, "1. e4 1... e5 1-0")]
+    #[test_case(Outcome::Decisive { winner: Color::Black }, "1. e4 1... e5 0-1")]
+    #[test_case(Outcome::Draw, "1. e4 1... e5 1/2-1/2")]
+    #[test_case(Outcome::Other, "1. e4 1... e5 *")]
+    fn to_pgn(outcome: Outcome, pgn: &str) {
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "e4", "e5").unwrap();
+
+        assert_eq!(var.to_pgn(outcome), pgn);
+        assert_eq!(Variation::new(Chess::new(), TurnsCapacity(0)).to_pgn(outcome), outcome.to_string());
+    }
+
+    #[test_case("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", MoveNumber(0))]
+    #[test_case("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1", MoveNumber(1))]
+    #[test_case("4k3/8/8/8/8/8/4P3/4K3 b - - 0 20", MoveNumber(39))]
+    #[test_case("4k3/8/8/8/8/8/4P3/4K3 w - - 0 20", MoveNumber(38))]
+    fn first_move_number(fen: &str, move_number: MoveNumber) {
+        let position = Fen::from_ascii(fen.as_bytes()).unwrap().into_position(shakmaty::CastlingMode::Standard).unwrap();
+        let var = Variation::new(position, TurnsCapacity::default());
+
+        assert_eq!(var.first_move_number(), move_number);
+    }
+
+    #[test]
+    fn side_to_move_at() {
+        let var = variation_sample0();
+
+        assert_eq!(var.side_to_move_at(0), Some(Color::White));
+        assert_eq!(var.side_to_move_at(1), Some(Color::Black));
+        assert_eq!(var.side_to_move_at(6), Some(Color::White));
+        assert_eq!(var.side_to_move_at(7), Some(Color::Black));
+        assert_eq!(var.side_to_move_at(8), None);
+
+        let black_first = Fen::from_ascii(b"rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1")
+            .unwrap()
+            .into_position(shakmaty::CastlingMode::Standard)
+            .unwrap();
+        let mut var = Variation::new(black_first, TurnsCapacity::default());
+
+        play_san_strings!(var, "e5", "Nf3").unwrap();
+
+        assert_eq!(var.side_to_move_at(0), Some(Color::Black));
+        assert_eq!(var.side_to_move_at(1), Some(Color::White));
+        assert_eq!(var.side_to_move_at(2), Some(Color::Black));
+    }
+
+    #[test]
+    fn piece_count() {
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "e4", "d5", "exd5", "Qxd5").unwrap();
+
+        assert_eq!(var.piece_count_at(0), Some(32));
+        assert_eq!(var.piece_count_at(3), Some(31));
+        assert_eq!(var.piece_count_at(4), Some(30));
+        assert_eq!(var.turns().get(1).unwrap().piece_count_after(), 32);
+        assert_eq!(var.turns().get(3).unwrap().piece_count_after(), 30);
+    }
+
+    #[test]
+    fn to_long_algebraic() {
+        assert_eq!(variation_sample1().to_long_algebraic(), "g2-g4 e7-e5 f2-f3 Qd8-h4#");
+        // Subvariations aren't included.
+        assert_eq!(variation_sample0().to_long_algebraic(), "e2-e4 e7-e5 Ng1-f3 Nb8-c6 Bf1-c4 Ng8-f6 Nb1-c3");
+
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Bxc6", "dxc6", "O-O").unwrap();
+
+        assert_eq!(var.to_long_algebraic(), "e2-e4 e7-e5 Ng1-f3 Nb8-c6 Bf1-b5 a7-a6 Bb5xc6 d7xc6 O-O");
+        assert_eq!(Variation::new(Chess::new(), TurnsCapacity::default()).to_long_algebraic(), "");
+    }
+
+    #[test]
+    fn mainline_capture_and_check_count() {
+        let var = variation_sample1();
+
+        assert_eq!(var.mainline_capture_count(), 0);
+        assert_eq!(var.mainline_check_count(), 1);
+
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "e4", "d5", "exd5", "Qxd5", "Nc3", "Qe5+", "Be2", "Qxe2+").unwrap();
+
+        assert_eq!(var.mainline_capture_count(), 3);
+        assert_eq!(var.mainline_check_count(), 2);
+        // Subvariations aren't counted.
+        assert_eq!(variation_sample0().mainline_capture_count(), 0);
+    }
+
+    #[test]
+    fn black_moves_from_black_start() {
+        let mut var = Variation::new(variation_sample6().position_after_last_move().clone(), TurnsCapacity::default());
+
+        play_san_strings!(var, "e5", "Nf3", "Nc6").unwrap();
+
+        let black_moves = var.black_moves().map(|r#move| r#move.to().to_string()).collect::<Vec<_>>();
+
+        assert_eq!(black_moves, ["e5", "c6"]);
+        assert_eq!(var.white_moves().count(), 1);
+    }
+
+    #[test_case("d3", Some(vec![6]))]
+    #[test_case("Ng1f3", Some(vec![0, 0, 1, 0, 1, 0, 4]); "non-minimal disambiguation")]
+    // `Nf3` occurs in the `1. d4` subtree before it occurs in the mainline.
+    #[test_case("Nf3", Some(vec![0, 0, 1, 0, 1, 0, 4]))]
+    #[test_case("Nf6", Some(vec![0, 0, 1, 0, 1, 0, 1]))]
+    #[test_case("Bc5", Some(vec![5, 0, 0]))]
+    #[test_case("Qh5", None)]
+    fn find_san(san: &str, path: Option<Vec<usize>>) {
+        let var = variation_sample2();
+
+        assert_eq!(var.find_san(&San::from_str(san).unwrap()), path);
+
+        if let Some((turn_i, variation_path)) = path.as_deref().and_then(<[usize]>::split_last) {
+            let variation = var.get_variation_by_path(variation_path).unwrap();
+            let r#move = San::from_str(san).unwrap().to_move(variation.get_position(*turn_i).unwrap()).unwrap();
+
+            assert_eq!(variation.turns().get(*turn_i).unwrap().r#move(), &r#move);
+        }
+    }
+
+    #[test]
+    fn upsert_variation() {
+        let mut var = variation_sample0();
+        let bc5_var = var.get_variation_by_path(&[5, 0]).unwrap().clone();
+        let mut nd4_var = Variation::new(bc5_var.first_position().clone(), TurnsCapacity::default());
+
+        play_san_strings!(nd4_var, "Nd4").unwrap();
+
+        assert_eq!(var.upsert_variation(5, bc5_var.clone()), Ok(UpsertResult::AlreadyPresent));
+        assert_eq!(var.upsert_variation(5, nd4_var.clone()), Ok(UpsertResult::Added));
+        assert_eq!(var.upsert_variation(5, nd4_var), Ok(UpsertResult::AlreadyPresent));
+        assert_eq!(var.turns().get(5).unwrap().variation_count(), 2);
+        assert!(var.upsert_variation(4, bc5_var).is_err());
+    }
+
+    #[test]
+    fn classify_san() {
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "Nf3", "a6", "d3", "a5").unwrap();
+
+        let mut candidates = match var.classify_san(&San::from_str("Nd2").unwrap()) {
+            SanClassification::Ambiguous(candidates) => candidates.iter().map(|r#move| r#move.from().unwrap().to_string()).collect(),
+            _ => Vec::new(),
+        };
+
+        candidates.sort();
+
+        assert_eq!(candidates, ["b1", "f3"]);
+        assert_eq!(var.classify_san(&San::from_str("Nbd2").unwrap()), SanClassification::Legal(San::from_str("Nbd2").unwrap().to_move(var.position_after_last_move()).unwrap()));
+        assert_eq!(var.classify_san(&San::from_str("Nd4").unwrap()), SanClassification::Legal(San::from_str("Nd4").unwrap().to_move(var.position_after_last_move()).unwrap()));
+        assert_eq!(var.classify_san(&San::from_str("Qh5").unwrap()), SanClassification::Illegal);
+    }
+
+    #[test]
+    fn prune_to_depth() {
+        let mut var = variation_sample2();
+
+        var.prune_to_depth(1);
+
+        // `1. d4` loses `1... f5` and everything under it, but `3... Bc5` stays.
+        assert_eq!(var.total_turn_count(), 7 + 2 + 1);
+        assert!(!var.get_variation_by_path(&[0, 0]).unwrap().has_variations());
+
+        let mut var = variation_sample2();
+
+        var.prune_to_depth(0);
+
+        assert_eq!(var, variation_sample2().stripped_mainline());
+
+        let mut var = variation_sample2();
+
+        var.prune_to_depth(10);
+
+        assert_eq!(var, variation_sample2());
+    }
+
+    #[test]
+    fn dedup_all() {
+        let expected = variation_sample0();
+        let mut var = variation_sample0();
+        let bc5_var = var.get_variation_by_path(&[5, 0]).unwrap().clone();
+        let d4_var = var.get_variation_by_path(&[0, 0]).unwrap().clone();
+        let f5_var = d4_var.get_variation_by_path(&[1, 0]).unwrap().clone();
+
+        var.insert_variation(5, bc5_var.clone()).unwrap();
+        var.insert_variation(5, bc5_var).unwrap();
+        var.get_variation_by_path_mut(&[0, 0]).unwrap().insert_variation(1, f5_var).unwrap();
+        var.insert_variation(0, d4_var).unwrap();
+
+        assert_eq!(var.turns().get(5).unwrap().variation_count(), 3);
+
+        var.dedup_all();
+
+        assert_eq!(var, expected);
+    }
+
+    #[test]
+    fn capacity_stats() {
+        let stats = variation_sample0().capacity_stats();
+
+        assert_eq!(stats.turns_len, 7 + 2 + 1 + 1);
+        assert_eq!(stats.variations_len, 3);
+        assert!(stats.turns_capacity >= stats.turns_len);
+        assert!(stats.variations_capacity >= stats.variations_len);
+    }
+
+    #[test]
+    fn get_san_plus() {
+        let mut var = Variation::new(Chess::new(), TurnsCapacity::default());
+
+        play_san_strings!(var, "Nf3", "a6", "d3", "a5", "Nbd2", "e5", "g4", "d6", "f3", "Qh4").unwrap();
+
+        assert_eq!(var.get_san_plus(0).unwrap().to_string(), "Nf3");
+        assert_eq!(var.get_san_plus(4).unwrap().to_string(), "Nbd2");
+        assert_eq!(var.get_san_plus(9).unwrap().to_string(), "Qh4+");
+        assert_eq!(var.get_san_plus(10), None);
+    }
+
+    #[test_case(&variation_sample0())]
+    #[test_case(&variation_sample1())]
+    #[test_case(&variation_sample2())]
+    #[test_case(&variation_sample6())]
+    fn position_after(var: &Variation) {
+        let len = var.turns().len();
+
+        for index in 0..len {
+            assert_eq!(var.position_after(index), var.get_position(index.saturating_add(1)));
+        }
+
+        assert_eq!(var.position_after(len), None);
+        assert_eq!(var.position_after(len.saturating_sub(1)), Some(var.position_after_last_move()));
+    }
+
+    #[test]
+    fn san_plus_from_move() {
+        let position = Chess::new();
+        let r#move = San::from_str("e4").unwrap().to_move(&position).unwrap();
+
+        assert_eq!(super::san_plus_from_move(&position, &r#move).to_string(), "e4");
+
+        let mut var = variation_sample1();
+        let checkmate = var.pop().unwrap();
+
+        assert_eq!(super::san_plus_from_move(var.position_after_last_move(), checkmate.r#move()).to_string(), "Qh4#");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,141 @@
+//! (De)serialization of [`Variation`] as a tree of `{ san, fen, variations }` nodes.
+//!
+//! A variation is serialized as `{ "fen": <first position>, "turns": [..] }`
+//! and every turn as `{ "san": <SAN with suffix>, "fen": <position after the move>, "variations": [..] }`.
+//! Deserialization replays the SANs from the first position, so the turn FENs are ignored.
+
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use shakmaty::{Chess, EnPassantMode};
+use shakmaty::fen::Fen;
+use shakmaty::san::SanPlus;
+use crate::{TurnsCapacity, Variation, VariationsCapacity};
+use crate::pgn::position_from_fen;
+
+#[derive(Serialize, Deserialize)]
+struct VariationNode {
+    fen: String,
+    turns: Vec<TurnNode>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TurnNode {
+    san: String,
+    fen: String,
+    variations: Vec<VariationNode>,
+}
+
+fn fen_string(position: &Chess) -> String {
+    Fen::from_position(position.clone(), EnPassantMode::Legal).to_string()
+}
+
+impl From<&Variation> for VariationNode {
+    fn from(variation: &Variation) -> Self {
+        let mut turns = Vec::with_capacity(variation.turns().len());
+
+        for (turn_i, turn) in variation.turns().iter().enumerate() {
+            // CLIPPY: `turn_i` is the index of an existing turn.
+            #[allow(clippy::unwrap_used)]
+            let san_plus = variation.get_san_plus(turn_i).unwrap();
+
+            turns.push(TurnNode {
+                san: san_plus.to_string(),
+                fen: fen_string(turn.position_after()),
+                variations: turn.variations().iter().map(Self::from).collect(),
+            });
+        }
+
+        Self {
+            fen: fen_string(variation.first_position()),
+            turns,
+        }
+    }
+}
+
+impl TryFrom<VariationNode> for Variation {
+    type Error = String;
+
+    fn try_from(node: VariationNode) -> Result<Self, Self::Error> {
+        let first_position = position_from_fen(&node.fen).map_err(|error| format!("{error:?}"))?;
+        let mut variation = Self::new(first_position, TurnsCapacity(node.turns.len()));
+
+        for (turn_i, turn) in node.turns.into_iter().enumerate() {
+            let san_plus = SanPlus::from_str(&turn.san).map_err(|error| error.to_string())?;
+
+            variation.play_san(&san_plus.san, VariationsCapacity(turn.variations.len())).map_err(|error| format!("{error:?}"))?;
+
+            for subvariation in turn.variations {
+                variation.insert_variation(turn_i, Self::try_from(subvariation)?).map_err(|error| format!("{error:?}"))?;
+            }
+        }
+
+        Ok(variation)
+    }
+}
+
+impl Serialize for Variation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        VariationNode::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Variation {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        Self::try_from(VariationNode::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::samples::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case(variation_sample0())]
+    #[test_case(variation_sample1())]
+    #[test_case(variation_sample2())]
+    #[test_case(variation_sample6())]
+    fn round_trip(var: Variation) {
+        let json = serde_json::to_string(&var).unwrap();
+
+        assert_eq!(serde_json::from_str::<Variation>(&json).unwrap(), var);
+    }
+
+    #[test]
+    fn chess960_round_trip() {
+        let mut var = Variation::new(position_from_fen("bqnbrkrn/pppppppp/8/8/8/8/PPPPPPPP/BQNBRKRN w KQkq - 0 1").unwrap(), TurnsCapacity::default());
+
+        crate::variation::play_san_strings!(var, "O-O", "O-O").unwrap();
+
+        let json = serde_json::to_string(&var).unwrap();
+
+        assert_eq!(serde_json::from_str::<Variation>(&json).unwrap(), var);
+    }
+
+    #[test]
+    fn shape() {
+        let json = serde_json::to_value(variation_sample6()).unwrap();
+
+        assert_eq!(json, serde_json::json!({
+            "fen": "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "turns": [{
+                "san": "e4",
+                "fen": "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+                "variations": []
+            }]
+        }));
+    }
+
+    #[test]
+    fn illegal_san() {
+        let json = serde_json::json!({
+            "fen": "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "turns": [{ "san": "e5", "fen": "", "variations": [] }]
+        });
+
+        assert!(serde_json::from_value::<Variation>(json).is_err());
+    }
+}